@@ -1,7 +1,4 @@
-use std::{fmt, io, time::{Duration, Instant}};
-
-use num_traits::{FromPrimitive, ToPrimitive};
-use num_derive::{FromPrimitive, ToPrimitive};
+use std::{fmt, io, thread::{self, JoinHandle}, time::{Duration, Instant}};
 
 use ratatui::{
     prelude::*,
@@ -11,18 +8,33 @@ use ratatui::{
     },
     crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind},
 };
+use chrono::Utc;
+use rand::seq::SliceRandom;
 use tachyonfx::{fx, EffectManager};
 use throbber_widgets_tui::{ThrobberState};
+use color_eyre::Result;
 
 use crate::{
-    components::{crew::CrewStatus, galaxy_map::GalacticMap, resources::Resources, star_map::StarMap}, storage::Storage, tui, user::User, util::{self, Event}
+    components::{admin::{AdminConsole, AdminView}, crew::{CrewStatus, CrewView}, event_popup::EventPopup, logbook::{Logbook, LogbookView}, galaxy_map::GalacticMap, resources::Resources, star_map::StarMap}, events::{EventStatus, Marker, Outcome, QueuedEvent}, logbook::{LogEntry, LogKind}, storage::Storage, tui, crew::{self, SHIP_LOCATION}, user::{Role, Ship, User}, util::{self, Event}
 };
 
-#[derive(Debug, Copy, Clone, FromPrimitive, ToPrimitive)]
+/// Changes are saved at most this often
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(10);
+// Keep the indicator up for a moment, otherwise it is only a flicker
+const SAVE_INDICATOR_DURATION: Duration = Duration::from_secs(1);
+
+pub enum AppExit {
+    Logout,
+    Quit,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 enum MenuItem {
-    GalacticMap = 0,
+    GalacticMap,
     StarMap,
     Crew,
+    Logbook,
+    Admin,
 }
 
 impl fmt::Display for MenuItem {
@@ -31,6 +43,8 @@ impl fmt::Display for MenuItem {
             MenuItem::GalacticMap => "Sterren kaart",
             MenuItem::StarMap => "Zonnestelsels",
             MenuItem::Crew => "Crew",
+            MenuItem::Logbook => "Logboek",
+            MenuItem::Admin => "Leiding",
         };
         write!(f, "{}", res)
     }
@@ -39,34 +53,40 @@ impl fmt::Display for MenuItem {
 #[derive(Debug)]
 struct MenuState {
     list_state: ListState,
-    selected: MenuItem,
+    items: Vec<MenuItem>,
     active: MenuItem,
 }
 
 impl MenuState {
-    fn select(&mut self, offset: i8) {
-        let current = self.selected as i8;
-        let next = current + offset;
-        if next == -1 { 
-            // Set to last item in the list
-            self.selected = MenuItem::Crew
-        } else {
-            self.selected = match FromPrimitive::from_i8(next) {
-                Some(d2) => d2,
-                None => FromPrimitive::from_u8(0).unwrap(),
-            };
+    fn new(role: Role) -> Self {
+        let mut items = vec![MenuItem::GalacticMap, MenuItem::StarMap, MenuItem::Crew, MenuItem::Logbook];
+        if role == Role::GameMaster {
+            items.push(MenuItem::Admin);
         }
-        self.list_state.select(self.selected.to_usize());
+        MenuState {
+            list_state: ListState::default().with_selected(Some(0)),
+            items,
+            active: MenuItem::GalacticMap,
+        }
+    }
+
+    fn select(&mut self, offset: i8) {
+        let current = self.list_state.selected().unwrap_or(0) as i8;
+        // Wrap around at both ends of the list
+        let next = (current + offset).rem_euclid(self.items.len() as i8);
+        self.list_state.select(Some(next as usize));
     }
 
     fn activate(&mut self) {
-        self.active = self.selected;
+        if let Some(selected) = self.list_state.selected() {
+            self.active = self.items[selected];
+        }
     }
 }
 
 pub struct App {
     // UI
-    exit: bool,
+    exit: Option<AppExit>,
     last_key_pressed: Option<event::KeyEvent>,
     last_press_time: Instant,
     effects: EffectManager<()>,
@@ -75,13 +95,22 @@ pub struct App {
     // Data
     storage: Storage,
     pub user: User,
+    unsaved_changes: bool,
+    last_save: Instant,
+    save_started: Option<Instant>,
+    save_thread: Option<JoinHandle<Result<()>>>,
+    save_error: Option<String>,
 
     // Sub components
     menu: MenuState,
     starmap: Option<StarMap>,
     galaxy: GalacticMap,
     crew: CrewStatus,
-    event: bool,
+    admin: AdminConsole,
+    logbook: Logbook,
+    event_popup: Option<EventPopup>,
+    /// Planet of the last random event, its outcomes hit the away team there
+    event_planet: Option<String>,
 }
 
 impl App {
@@ -93,9 +122,12 @@ impl App {
         );
        
         let pos = (user.pos_x, user.pos_y);
+        let role = user.role;
+        let markers = user.markers.clone();
+        let discovered = user.discovered.clone();
         let solar_systems = storage.map.clone();
         let mut result = Self {
-            exit: false,
+            exit: None,
             last_key_pressed: None,
             last_press_time: Instant::now(),
 
@@ -104,30 +136,42 @@ impl App {
 
             storage,
             user,
+            unsaved_changes: false,
+            last_save: Instant::now(),
+            save_started: None,
+            save_thread: None,
+            save_error: None,
 
-            menu: MenuState {
-                list_state: ListState::default().with_selected(Some(0)),
-                selected: MenuItem::GalacticMap,
-                active: MenuItem::GalacticMap,
-            },
+            menu: MenuState::new(role),
             starmap: None,
-            galaxy: GalacticMap::new(solar_systems.clone(), pos),
-            crew: CrewStatus{},
-            event: false,
+            galaxy: GalacticMap::new(solar_systems.clone(), markers, discovered, pos),
+            crew: CrewStatus::default(),
+            admin: AdminConsole::default(),
+            logbook: Logbook::default(),
+            event_popup: None,
+            event_planet: None,
         };
         result.galaxy.update_system();
+        // Show the readings of the last sweep again
+        if let Some(LogKind::Sweep { readings, .. }) = result.user.logbook.iter().rev()
+            .map(|entry| &entry.kind)
+            .find(|kind| matches!(kind, LogKind::Sweep { .. }))
+        {
+            result.galaxy.signals = readings.clone();
+        }
+        result.sync_ship();
         if let Some(system) = result.galaxy.get_current_system() {
             result.starmap = Some(system.to_star_map());
         } 
         result
     }
 
-    pub fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<()> {
+    pub fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<AppExit> {
         let mut last_frame = Instant::now();
         let mut last_tick = Instant::now();
         let tick_rate = Duration::from_millis(200);
 
-        while !self.exit {
+        while self.exit.is_none() {
             let elapsed = last_frame.elapsed();
             if last_tick.elapsed() >= tick_rate {
                 self.on_tick();
@@ -139,18 +183,72 @@ impl App {
             self.handle_events()?;
 
         }
+        // Wait for a running autosave, so it can not overwrite the final save
+        self.finish_save(true);
+        self.storage = self.snapshot();
+        let _ = self.storage.clone().save();
+        Ok(self.exit.take().expect("exit to be set"))
+    }
+
+    /// Hand back the storage with everything this user did, for the next login
+    pub fn into_storage(self) -> Storage {
+        self.storage
+    }
+
+    fn on_tick(&mut self) {
+        self.throbber_state.calc_next();
+        self.finish_save(false);
+        if self.unsaved_changes && self.last_save.elapsed() >= AUTOSAVE_INTERVAL {
+            self.autosave();
+        }
+    }
+
+    /// The map keeps track of where the ship is and what it has seen, the user only when asked
+    fn sync_user(&mut self) {
         self.user.pos_x = self.galaxy.current_pos.0;
         self.user.pos_y = self.galaxy.current_pos.1;
+        self.user.discovered = self.galaxy.discovered.clone();
+    }
+
+    /// Copy of the storage with the current state of the user and map
+    fn snapshot(&mut self) -> Storage {
+        self.sync_user();
 
         let mut copy = self.storage.clone();
         copy.update_user(&self.user);
         copy.map = self.galaxy.solar_systems.clone();
-        let _ = copy.save();
-        Ok(())
+        copy
     }
 
-    fn on_tick(&mut self) {
-        self.throbber_state.calc_next();
+    /// Save in a background thread, so rendering does not have to wait on the disk
+    fn autosave(&mut self) {
+        if self.save_thread.is_some() {
+            return;
+        }
+        let copy = self.snapshot();
+        self.save_thread = Some(thread::spawn(move || copy.save()));
+        self.unsaved_changes = false;
+        self.last_save = Instant::now();
+        self.save_started = Some(self.last_save);
+    }
+
+    fn finish_save(&mut self, wait: bool) {
+        let done = match &self.save_thread {
+            Some(handle) => wait || handle.is_finished(),
+            None => false,
+        };
+        if done {
+            let result = self.save_thread.take().expect("save thread to exist").join();
+            self.save_error = match result {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(err.to_string()),
+                Err(_) => Some(String::from("save thread panicked")),
+            };
+        }
+    }
+
+    fn is_saving(&self) -> bool {
+        self.save_thread.is_some() || self.save_started.is_some_and(|start| start.elapsed() < SAVE_INDICATOR_DURATION)
     }
 
     fn render_frame(&mut self, frame: &mut Frame, elapsed: Duration) {
@@ -169,40 +267,50 @@ impl App {
                         self.last_key_pressed = Some(key);
                     }
 
-                    self.handle_press_event(key);
+                    // An open event has to be resolved first
+                    if let Some(popup) = &mut self.event_popup {
+                        let title = popup.title().to_string();
+                        let choice = popup.handle_press_event(key);
+                        if popup.closed {
+                            self.event_popup = None;
+                        }
+                        if let Some(choice) = choice {
+                            self.handle_event(Event::Log(LogKind::EventChoice { title, choice: choice.label }));
+                            for outcome in choice.outcomes {
+                                self.apply_outcome(outcome);
+                            }
+                        }
+                        return Ok(());
+                    }
+
+                    if !self.captures_input() {
+                        self.handle_press_event(key);
+                    }
                     let events = match self.menu.active {
                         MenuItem::GalacticMap => { 
-                            self.galaxy.handle_press_event(key, self.last_key_pressed, self.last_press_time, self.user.fuel > 0)
+                            self.galaxy.handle_press_event(key, self.last_key_pressed, self.last_press_time)
                         },
                         MenuItem::StarMap => { 
                             if let Some(map) = &mut self.starmap {
-                                map.handle_press_event(key, self.last_key_pressed, self.last_press_time, self.user.username.clone())
+                                map.handle_press_event(key, self.last_key_pressed, self.last_press_time, self.user.username.clone(), &self.user.crew)
                             } else { Vec::new() }
                         },
-                        _ => { Vec::new() }
+                        MenuItem::Crew => {
+                            self.crew.handle_press_event(key, &mut self.user.crew)
+                        },
+                        MenuItem::Logbook => {
+                            self.logbook.handle_press_event(key, &self.user.logbook);
+                            Vec::new()
+                        },
+                        MenuItem::Admin => {
+                            // The console edits the stored users, including the game master's own ship
+                            self.sync_user();
+                            self.storage.update_user(&self.user);
+                            self.admin.handle_press_event(key, &mut self.storage)
+                        },
                     };
                     for event in events {
-                        match event {
-                            Event::Item(diff) => {
-                                self.user.crystals += diff.crystals;
-                                self.user.fuel += diff.fuel;
-                                self.storage.components += diff.components;
-                                // Earn 1 reputation per component
-                                self.user.reputation += diff.components;
-                            },
-                            Event::NewSystem(Some(system)) => {
-                                self.starmap = Some(system.to_star_map());
-                            },
-                            Event::NewSystem(None) => { self.starmap = None; },
-                            Event::PlanetUpdate => {
-                                if let Some(system) = self.galaxy.get_current_system_mut() {
-                                    system.planets = self.starmap.as_ref().expect("starmap just handled input").planets.clone();
-                                }
-                            },
-                            Event::RandomEvent => {
-                                self.event = true;
-                            },
-                        }
+                        self.handle_event(event);
                     }
                 };       
             }
@@ -213,10 +321,189 @@ impl App {
         Ok(())
     }
 
+    fn handle_event(&mut self, event: Event) {
+        self.unsaved_changes = true;
+        match event {
+            Event::Item(diff) => {
+                self.user.crystals += diff.crystals;
+                self.user.fuel += diff.fuel;
+                self.storage.components += diff.components;
+                // Earn 1 reputation per component
+                self.user.reputation += diff.components;
+            },
+            Event::NewSystem(Some(system)) => {
+                self.starmap = Some(system.to_star_map());
+            },
+            Event::NewSystem(None) => { self.starmap = None; },
+            Event::PlanetUpdate => {
+                if let Some(system) = self.galaxy.get_current_system_mut() {
+                    system.planets = self.starmap.as_ref().expect("starmap just handled input").planets.clone();
+                }
+            },
+            Event::RandomEvent(planet) => {
+                let planet_name = planet.clone();
+                let definition = self.storage.events.choose(&mut rand::thread_rng()).cloned();
+                let title = definition.as_ref().map(|definition| definition.title.clone()).unwrap_or_default();
+                self.user.logbook.push(LogEntry::new(LogKind::RandomEvent {
+                    planet: planet.clone(),
+                    title: title.clone(),
+                }));
+                // Let de leiding know, they decide what really happens
+                self.storage.event_queue.push(QueuedEvent {
+                    user: self.user.username.clone(),
+                    planet,
+                    system: self.galaxy.get_current_system().map(|system| system.name.clone()).unwrap_or_default(),
+                    title,
+                    timestamp: Utc::now().timestamp(),
+                    status: EventStatus::Pending,
+                });
+                if let Some(definition) = definition {
+                    self.event_popup = Some(EventPopup::new(definition));
+                }
+                self.event_planet = Some(planet_name);
+            },
+            Event::Log(kind) => {
+                self.user.logbook.push(LogEntry::new(kind));
+            },
+            Event::UpgradeShip => {
+                let cost = self.user.ship.upgrade_cost();
+                if self.user.crystals >= cost {
+                    self.user.crystals -= cost;
+                    self.user.ship.upgrade();
+                } else {
+                    self.galaxy.notify(format!("Te weinig kristallen voor een upgrade, nodig: {}", cost));
+                }
+            },
+            // Only has to be saved
+            Event::CrewUpdate => {},
+            Event::Land { planet, party } => {
+                for member in self.user.crew.iter_mut().filter(|member| party.contains(&member.name)) {
+                    member.location = planet.clone();
+                }
+            },
+            Event::Recall => {
+                let party: Vec<String> = self.user.crew.iter_mut()
+                    .filter(|member| member.location != SHIP_LOCATION)
+                    .map(|member| {
+                        member.location = String::from(SHIP_LOCATION);
+                        member.name.clone()
+                    })
+                    .collect();
+                if !party.is_empty() {
+                    self.user.logbook.push(LogEntry::new(LogKind::Recall { party }));
+                }
+            },
+            Event::SensorSweep => {
+                if self.user.crystals >= Ship::SWEEP_COST {
+                    self.user.crystals -= Ship::SWEEP_COST;
+                    let kind = self.galaxy.sweep();
+                    self.user.logbook.push(LogEntry::new(kind));
+                } else {
+                    self.galaxy.notify(format!("Te weinig kristallen voor een scan, nodig: {}", Ship::SWEEP_COST));
+                }
+            },
+            Event::UserUpdate(username) => {
+                // The game master edited their own ship
+                if username == self.user.username {
+                    if let Some(user) = self.storage.users.iter().find(|u| u.username == username) {
+                        self.user = user.clone();
+                    }
+                    // Otherwise the next save puts the ship back where the map still has it
+                    let pos = (self.user.pos_x, self.user.pos_y);
+                    if pos != self.galaxy.current_pos {
+                        for event in self.galaxy.teleport(pos) {
+                            self.handle_event(event);
+                        }
+                    }
+                }
+            },
+        }
+        self.sync_ship();
+    }
+
+    fn apply_outcome(&mut self, outcome: Outcome) {
+        self.unsaved_changes = true;
+        match outcome {
+            Outcome::Resources { crystals, fuel, reputation } => {
+                // An event takes what there is, reputation is the only thing that can go negative
+                self.user.crystals = (self.user.crystals + crystals).max(0);
+                self.user.fuel = (self.user.fuel + fuel).max(0);
+                self.user.reputation += reputation;
+            },
+            Outcome::CrewDamage { amount } => {
+                // Hits a random crew member who can still take it
+                let mut rng = rand::thread_rng();
+                if let Some(i) = self.affected_crew().choose(&mut rng) {
+                    let member = &mut self.user.crew[*i];
+                    member.damage(amount);
+                    let name = member.name.clone();
+                    self.handle_event(Event::Log(LogKind::Injury { name, amount }));
+                }
+            },
+            Outcome::Stress { amount } => {
+                let amount = i8::try_from(amount).unwrap_or(i8::MAX);
+                for i in self.affected_crew() {
+                    self.user.crew[i].add_stress(amount);
+                }
+            },
+            Outcome::PanicCheck => {
+                let mut rng = rand::thread_rng();
+                let checks: Vec<LogKind> = self.affected_crew().into_iter()
+                    .map(|i| {
+                        let member = &mut self.user.crew[i];
+                        member.panic_check(&mut rng).log(&member.name)
+                    })
+                    .collect();
+                for kind in checks {
+                    self.handle_event(Event::Log(kind));
+                }
+            },
+            Outcome::Teleport { pos } => {
+                // The away team is pulled along, otherwise the ship is stranded without them
+                if crew::has_away_team(&self.user.crew) {
+                    self.handle_event(Event::Recall);
+                }
+                let mut rng = rand::thread_rng();
+                let pos = pos.unwrap_or_else(|| self.galaxy.random_position(&mut rng));
+                for event in self.galaxy.teleport(pos) {
+                    self.handle_event(event);
+                }
+            },
+            Outcome::Marker { name, pos } => {
+                let marker = Marker { name, pos: pos.unwrap_or(self.galaxy.current_pos) };
+                self.user.markers.push(marker.clone());
+                self.galaxy.markers.push(marker);
+            },
+        }
+        self.sync_ship();
+    }
+
+    /// Whether the active screen wants all keys, for instance to type in
+    fn captures_input(&self) -> bool {
+        match self.menu.active {
+            MenuItem::GalacticMap => self.galaxy.captures_input(),
+            MenuItem::StarMap => self.starmap.as_ref().is_some_and(|map| map.captures_input()),
+            MenuItem::Crew => self.crew.captures_input(),
+            _ => false,
+        }
+    }
+
+    /// The galactic map needs the ship stats and fuel to plan warps
+    fn sync_ship(&mut self) {
+        self.galaxy.ship = self.user.ship.clone();
+        self.galaxy.fuel = self.user.fuel;
+        self.galaxy.away_team = crew::has_away_team(&self.user.crew);
+    }
+
+    fn affected_crew(&self) -> Vec<usize> {
+        crew::affected_crew(&self.user.crew, self.event_planet.as_deref())
+    }
+
     fn handle_press_event(&mut self, key_event: KeyEvent) {
         match key_event.code {
             // Key's for all widgets
-            KeyCode::Esc        => { self.exit = true; },
+            KeyCode::Esc        => { self.exit = Some(AppExit::Quit); },
+            KeyCode::Char('l')  => { self.exit = Some(AppExit::Logout); },
             // Other
             KeyCode::Up         => { self.menu.select(-1); },
             KeyCode::Down       => { self.menu.select(1); },
@@ -226,7 +513,6 @@ impl App {
                 // TODO: apply the effect only to the submodule / widget in the screen
                 // self.effects.add_effect(fx::coalesce(1000));
             },
-            KeyCode::Char('q') => { self.event = false; }
             _ => {},
         }
     }
@@ -239,6 +525,8 @@ impl App {
             "<Up>".green().bold(),
             " Move down ".into(),
             "<Down>".green().bold(),
+            " Uitloggen ".into(),
+            "<l>".green().bold(),
             " Quit ".into(),
             "<Esc> ".green().bold(),
         ]);
@@ -267,16 +555,15 @@ impl App {
             Constraint::Percentage(25),
         ]).areas(area);
 
-        let gmap = match self.user.fuel > 0 {
-            true =>  Line::from(MenuItem::GalacticMap.to_string()),
-            false =>  Line::from(MenuItem::GalacticMap.to_string()).crossed_out(),
-        };
+        let items = self.menu.items.iter().map(|item| {
+            let line = Line::from(item.to_string()).alignment(Alignment::Center);
+            match item {
+                MenuItem::GalacticMap if self.user.fuel <= 0 => line.crossed_out(),
+                _ => line,
+            }
+        });
 
-        let menu = List::new([
-            gmap.alignment(Alignment::Center),
-            Line::from(MenuItem::StarMap.to_string()).alignment(Alignment::Center),
-            Line::from(MenuItem::Crew.to_string()).alignment(Alignment::Center),
-        ])
+        let menu = List::new(items)
             .style(Style::default().fg(Color::White))
             .highlight_style(Style::default()
                 .bold()
@@ -308,7 +595,36 @@ impl Widget for &mut App {
         self.render_title(title, buf);
         self.render_list(list, buf);
 
-        // TODO: render current planet stats
+        let [save_status, event_status] = Layout::vertical([
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ]).areas(status);
+
+        if let Some(err) = &self.save_error {
+            Paragraph::new(format!("Opslaan mislukt: {}", err))
+                .red()
+                .centered()
+                .render(save_status, buf);
+        } else if self.is_saving() {
+            let saving = throbber_widgets_tui::Throbber::default()
+                .label("Opslaan...")
+                .style(Style::default().fg(Color::Cyan))
+                .throbber_style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+                .throbber_set(throbber_widgets_tui::BLACK_CIRCLE)
+                .use_type(throbber_widgets_tui::WhichUse::Spin);
+            ratatui::prelude::StatefulWidget::render(saving, save_status, buf, &mut self.throbber_state);
+        }
+
+        // Stays until de leiding has resolved the event
+        if self.storage.has_pending_events(&self.user.username) {
+            let full = throbber_widgets_tui::Throbber::default()
+                .label("RANDOM EVENT! ga naar de leiding!")
+                .style(ratatui::style::Style::default().fg(ratatui::style::Color::Cyan))
+                .throbber_style(ratatui::style::Style::default().fg(ratatui::style::Color::Red).add_modifier(ratatui::style::Modifier::BOLD))
+                .throbber_set(throbber_widgets_tui::BLACK_CIRCLE)
+                .use_type(throbber_widgets_tui::WhichUse::Spin);
+            ratatui::prelude::StatefulWidget::render(full, event_status, buf, &mut self.throbber_state);
+        }
 
         // if self.user.fuel == 0 {
         //     let full = throbber_widgets_tui::Throbber::default()
@@ -320,16 +636,6 @@ impl Widget for &mut App {
         //     ratatui::prelude::StatefulWidget::render(full, status, buf, &mut self.throbber_state);
         // }
 
-        // if self.event {
-        //     let full = throbber_widgets_tui::Throbber::default()
-        //         .label("RANDOM EVENT! ga naar de leiding!")
-        //         .style(ratatui::style::Style::default().fg(ratatui::style::Color::Cyan))
-        //         .throbber_style(ratatui::style::Style::default().fg(ratatui::style::Color::Red).add_modifier(ratatui::style::Modifier::BOLD))
-        //         .throbber_set(throbber_widgets_tui::BLACK_CIRCLE)
-        //         .use_type(throbber_widgets_tui::WhichUse::Spin);
-        //     ratatui::prelude::StatefulWidget::render(full, status, buf, &mut self.throbber_state);
-        // }
-
         Resources {
             crystals: self.user.crystals,
             fuel: self.user.fuel,
@@ -352,7 +658,30 @@ impl Widget for &mut App {
                     map.render(inner, buf);
                 }
             },
-            MenuItem::Crew      => { self.crew.render(inner, buf); },
+            MenuItem::Crew      => {
+                CrewView {
+                    status: &self.crew,
+                    crew: &self.user.crew,
+                }.render(inner, buf);
+            },
+            MenuItem::Logbook   => {
+                LogbookView {
+                    logbook: &self.logbook,
+                    entries: &self.user.logbook,
+                }.render(inner, buf);
+            },
+            MenuItem::Admin     => {
+                AdminView {
+                    console: &self.admin,
+                    users: &self.storage.users,
+                    queue: &self.storage.event_queue,
+                    solar_systems: &self.galaxy.solar_systems,
+                }.render(inner, buf);
+            },
+        }
+
+        if let Some(popup) = &self.event_popup {
+            popup.render(inner, buf);
         }
     }
 }
@@ -0,0 +1,212 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent}, prelude::*, widgets::{Block, Cell, Paragraph, Row, Table, Widget, Wrap}
+};
+
+use crate::{events::{EventStatus, QueuedEvent}, objects::SolarSystem, storage::Storage, user::User, util::{format_timestamp, Event}};
+
+const FIELDS: [&str; 6] = ["Positie x", "Positie y", "Brandstof", "Kristallen", "Reputatie", "Warp bereik"];
+const POS_STEP: f64 = 0.5;
+
+#[derive(Default, PartialEq)]
+enum Tab {
+    #[default]
+    Ships,
+    Queue,
+}
+
+/// Game master console, lists every ship and allows editing them live
+#[derive(Default)]
+pub struct AdminConsole {
+    tab: Tab,
+    selected_user: usize,
+    selected_field: usize,
+    selected_event: usize,
+}
+
+impl AdminConsole {
+    pub fn handle_press_event(&mut self, key_event: KeyEvent, storage: &mut Storage) -> Vec<Event> {
+        if key_event.code == KeyCode::Tab {
+            self.tab = match self.tab {
+                Tab::Ships => Tab::Queue,
+                Tab::Queue => Tab::Ships,
+            };
+            return Vec::new();
+        }
+        match self.tab {
+            Tab::Ships => self.handle_ships(key_event, &mut storage.users),
+            Tab::Queue => self.handle_queue(key_event, &mut storage.event_queue),
+        }
+    }
+
+    fn handle_queue(&mut self, key_event: KeyEvent, queue: &mut [QueuedEvent]) -> Vec<Event> {
+        if queue.is_empty() {
+            return Vec::new();
+        }
+        // The queue is shown newest first
+        let index = queue.len() - 1 - self.selected_event.min(queue.len() - 1);
+        let status = match key_event.code {
+            KeyCode::Char('w') => { self.selected_event = (self.selected_event + queue.len() - 1) % queue.len(); None },
+            KeyCode::Char('s') => { self.selected_event = (self.selected_event + 1) % queue.len(); None },
+            KeyCode::Char('a') => Some(EventStatus::Acknowledged),
+            KeyCode::Char('r') => Some(EventStatus::Resolved),
+            _ => None,
+        };
+        match status {
+            Some(status) => {
+                queue[index].status = status;
+                vec![Event::UserUpdate(queue[index].user.clone())]
+            },
+            None => Vec::new(),
+        }
+    }
+
+    fn handle_ships(&mut self, key_event: KeyEvent, users: &mut [User]) -> Vec<Event> {
+        if users.is_empty() {
+            return Vec::new();
+        }
+        let step = match key_event.code {
+            KeyCode::Char('w') => { self.selected_user = (self.selected_user + users.len() - 1) % users.len(); 0 },
+            KeyCode::Char('s') => { self.selected_user = (self.selected_user + 1) % users.len(); 0 },
+            KeyCode::Char('a') => { self.selected_field = (self.selected_field + FIELDS.len() - 1) % FIELDS.len(); 0 },
+            KeyCode::Char('d') => { self.selected_field = (self.selected_field + 1) % FIELDS.len(); 0 },
+            KeyCode::Char('+') => 1,
+            KeyCode::Char('-') => -1,
+            _ => 0,
+        };
+        if step == 0 {
+            return Vec::new();
+        }
+
+        let user = &mut users[self.selected_user.min(users.len() - 1)];
+        match self.selected_field {
+            0 => user.pos_x += POS_STEP * step as f64,
+            1 => user.pos_y += POS_STEP * step as f64,
+            2 => user.fuel += step,
+            3 => user.crystals += step,
+            4 => user.reputation += step,
+            _ => user.ship.warp_range = (user.ship.warp_range + POS_STEP * step as f64).max(0.0),
+        }
+        vec![Event::UserUpdate(user.username.clone())]
+    }
+}
+
+pub struct AdminView<'a> {
+    pub console: &'a AdminConsole,
+    pub users: &'a [User],
+    pub queue: &'a [QueuedEvent],
+    pub solar_systems: &'a [SolarSystem],
+}
+
+impl AdminView<'_> {
+    fn visited_planets(&self, user: &User) -> Vec<String> {
+        self.solar_systems.iter()
+            .flat_map(|system| &system.planets)
+            .filter(|planet| planet.visited_by.contains(&user.username))
+            .map(|planet| planet.name.clone())
+            .collect()
+    }
+
+    fn render_queue(&self, area: Rect, buf: &mut Buffer) {
+        let selected = self.console.selected_event.min(self.queue.len().saturating_sub(1));
+        let rows = self.queue.iter().rev().enumerate().map(|(i, event)| {
+            let status = match event.status {
+                EventStatus::Pending => Cell::from(event.status.to_string()).red().bold(),
+                EventStatus::Acknowledged => Cell::from(event.status.to_string()).yellow(),
+                EventStatus::Resolved => Cell::from(event.status.to_string()).dark_gray(),
+            };
+            let row = Row::new(vec![
+                Cell::from(format_timestamp(event.timestamp)),
+                Cell::from(event.user.clone()),
+                Cell::from(event.title.clone()),
+                Cell::from(event.planet.clone()),
+                Cell::from(event.system.clone()),
+                status,
+            ]);
+            if i == selected { row.green().bold() } else { row }
+        });
+
+        let table = Table::new(rows, [Constraint::Fill(1); 6])
+            .header(Row::new(["Tijd", "Schip", "Event", "Planeet", "Stelsel", "Status"]).underlined())
+            .block(Block::bordered()
+                .title(" Meldingen ")
+                .title_bottom(Line::from(vec![
+                    " Melding ".into(), "<w/s>".green().bold(),
+                    " Gezien ".into(), "<a>".green().bold(),
+                    " Afgehandeld ".into(), "<r>".green().bold(),
+                    " Schepen ".into(), "<Tab>".green().bold(), " ".into(),
+                ]))
+                .title_alignment(Alignment::Center));
+        Widget::render(table, area, buf);
+    }
+}
+
+impl Widget for AdminView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.console.tab == Tab::Queue {
+            self.render_queue(area, buf);
+            return;
+        }
+
+        let [table_area, details_area] = Layout::vertical([
+            Constraint::Percentage(60),
+            Constraint::Percentage(40),
+        ]).areas(area);
+
+        let selected_user = self.console.selected_user.min(self.users.len().saturating_sub(1));
+        let rows = self.users.iter().enumerate().map(|(i, user)| {
+            let values = [
+                format!("{:.1}", user.pos_x),
+                format!("{:.1}", user.pos_y),
+                user.fuel.to_string(),
+                user.crystals.to_string(),
+                user.reputation.to_string(),
+                format!("{:.1}", user.ship.warp_range),
+            ];
+            let mut cells = vec![Cell::from(user.username.clone())];
+            cells.extend(values.into_iter().enumerate().map(|(field, value)| {
+                let cell = Cell::from(value);
+                if i == selected_user && field == self.console.selected_field {
+                    cell.black().on_green()
+                } else {
+                    cell
+                }
+            }));
+            cells.push(Cell::from(self.visited_planets(user).len().to_string()));
+
+            let row = Row::new(cells);
+            if i == selected_user { row.green().bold() } else { row }
+        });
+
+        let pending = self.queue.iter().filter(|event| event.status == EventStatus::Pending).count();
+        let mut header = vec!["Schip"];
+        header.extend(FIELDS);
+        header.push("Bezocht");
+
+        let table = Table::new(rows, [Constraint::Fill(1); 8])
+            .header(Row::new(header).underlined())
+            .block(Block::bordered()
+                .title(" Schepen ")
+                .title(Line::from(format!(" {} nieuwe meldingen ", pending)).right_aligned().red().bold())
+                .title_bottom(Line::from(vec![
+                    " Schip ".into(), "<w/s>".green().bold(),
+                    " Waarde ".into(), "<a/d>".green().bold(),
+                    " Aanpassen ".into(), "<+/->".green().bold(),
+                    " Meldingen ".into(), "<Tab>".green().bold(), " ".into(),
+                ]))
+                .title_alignment(Alignment::Center));
+        Widget::render(table, table_area, buf);
+
+        let Some(user) = self.users.get(selected_user) else { return };
+        let visited = self.visited_planets(user);
+        let text = match visited.is_empty() {
+            true => String::from("Nog geen planeten bezocht"),
+            false => visited.join(", "),
+        };
+        Paragraph::new(text)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered()
+                .title(format!(" Bezochte planeten van {} ", user.username))
+                .title_alignment(Alignment::Center))
+            .render(details_area, buf);
+    }
+}
@@ -1,184 +1,318 @@
-use ratatui::{prelude::*, symbols::border, widgets::*};
-
-const YODA: &str = r#"
-                   ____                  
-                _.' :  `._               
-            .-.'`.  ;   .'`.-.           
-   __      / : ___\ ;  /___ ; \      __  
- ,'_ ""--.:__;".-.";: :".-.":__;.--"" _`,
- :' `.t""--.. '<@.`;_  ',@>` ..--""j.' `;
-      `:-.._J '-.-'L__ `-- ' L_..-;'     
-        "-.__ ;  .-"  "-.  : __.-"       
-            L ' /.------.\ ' J           
-             "-.   "--"   .-"            
-            __.l"-:_JL_;-";.__           
-         .-j/'.;  ;""""  / .'\"-.        
-       .' /:`. "-.:     .-" .';  `.      
-    .-"  / ;  "-. "-..-" .-"  :    "-.   
- .+"-.  : :      "-.__.-"      ;-._   \  
- ; \  `.; ;                    : : "+. ; 
- :  ;   ; ;                    : ;  : \: 
-"#;
-
-const BOB: &str = r#"
-      .--..--..--..--..--..--.
-    .' \  (`._   (_)     _   \
-  .'    |  '._)         (_)  |
-  \ _.')\      .----..---.   /
-  |(_.'  |    /    .-\-.  \  |
-  \     0|    |   ( O| O) | o|
-   |  _  |  .--.____.'._.-.  |
-   \ (_) | o         -` .-`  |
-    |    \   |`-._ _ _ _ _\ /
-    \    |   |  `. |_||_|   |
-    | o  |    \_      \     |     -.   .-.
-    |.-.  \     `--..-'   O |     `.`-' .'
-  _.'  .' |     `-.-'      /-.__   ' .-'
-.' `-.` '.|='=.='=.='=.='=|._/_ `-'.'
-`-._  `.  |________/\_____|    `-.'
-   .'   ).| '=' '='\/ '=' |
-   `._.`  '---------------'
-           //___\   //___\
-             ||       ||
-             ||_.-.   ||_.-.
-            (_.--__) (_.--__)
-"#;
-
-const SKELETON: &str = r#"
-      .-.
-     (o.o)
-      |=|
-     __|__
-   //.=|=.\\
-  // .=|=. \\
-  \\ .=|=. //
-   \\(_=_)//
-    (:| |:)
-     || ||
-     () ()
-     || ||
-     || ||
-    ==' '==
-"#;
-
-const ALIEN: &str = r#"
- o            o
-  \          /
-   \        /
-    :-'""'-:
- .-'  ____  `-.
-( (  (_()_)  ) )
- `-.   ^^   .-'
-    `._==_.'
-     __)(___
-"#;
-
-const XENOMORPH: &str = r#"
-         __.,,------.._
-      ,'"   _      _   "`.
-     /.__, ._  -=- _"`    Y
-    (.____.-.`      ""`   j
-     VvvvvvV`.Y,.    _.,-'       ,     ,     ,
-        Y    ||,   '"\         ,/    ,/    ./
-        |   ,'  ,     `-..,'_,'/___,'/   ,'/   ,
-   ..  ,;,,',-'"\,'  ,  .     '     ' ""' '--,/    .. ..
- ,'. `.`---'     `, /  , Y -=-    ,'   ,   ,. .`-..||_|| ..
-ff\\`. `._        /f ,'j j , ,' ,   , f ,  \=\ Y   || ||`||_..
-l` \` `.`."`-..,-' j  /./ /, , / , / /l \   \=\l   || `' || ||...
- `  `   `-._ `-.,-/ ,' /`"/-/-/-/-"'''"`.`.  `'.\--`'--..`'_`' || ,
-            "`-_,',  ,'  f    ,   /      `._    ``._     ,  `-.`'//         ,
-          ,-"'' _.,-'    l_,-'_,,'          "`-._ . "`. /|     `.'\ ,       |
-        ,',.,-'"          \=) ,`-.         ,    `-'._`.V |       \ // .. . /j
-        |f\\               `._ )-."`.     /|         `.| |        `.`-||-\\/
-        l` \`                 "`._   "`--' j          j' j          `-`---'
-         `  `                     "`,-  ,'/       ,-'"  /
-                                 ,'",__,-'       /,, ,-'
-                                 Vvv'            VVv'
-"#;
-
-const GUY: &str = r#"
-   .------\ /------.
-   |       -       |
-   |               |
-   |               |
-   |               |
-_______________________
-===========.===========
-  / ~~~~~     ~~~~~ \
- /|     |     |\
- W   ---  / \  ---   W
- \.      |o o|      ./
-  |                 |
-  \    #########    /
-   \  ## ----- ##  /
-    \##         ##/
-     \_____v_____/
-"#;
-
-struct CrewMember {
-    name: String,
-    picture: String,
-    role: String,
-    location: String,
-    vitality: u8,
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent}, prelude::*, symbols::border, widgets::*
+};
+
+use crate::{components::dice::{DiceRoller, DiceView}, crew::{condition_description, Class, CrewMember, MAX_STAT, MAX_STRESS}, util::Event};
+
+/// Rows of two cards that fit on the screen
+const VISIBLE_ROWS: usize = 3;
+
+const FIELDS: [&str; 16] = [
+    "Klasse", "Kracht", "Snelheid", "Intellect", "Gevecht", "Verstand", "Angst", "Lichaam",
+    "Gezondheid", "Max gezondheid", "Wonden", "Max wonden", "Stress", "Vaardigheden", "Uitrusting", "Condities",
+];
+const SKILLS_FIELD: usize = 13;
+const LOADOUT_FIELD: usize = 14;
+const CONDITIONS_FIELD: usize = 15;
+
+fn field_value(member: &CrewMember, field: usize) -> String {
+    match field {
+        0 => member.class.to_string(),
+        1 => member.stats.strength.to_string(),
+        2 => member.stats.speed.to_string(),
+        3 => member.stats.intellect.to_string(),
+        4 => member.stats.combat.to_string(),
+        5 => member.saves.sanity.to_string(),
+        6 => member.saves.fear.to_string(),
+        7 => member.saves.body.to_string(),
+        8 => member.health.to_string(),
+        9 => member.max_health.to_string(),
+        10 => member.wounds.to_string(),
+        11 => member.max_wounds.to_string(),
+        12 => member.stress.to_string(),
+        SKILLS_FIELD => member.skills.join(", "),
+        LOADOUT_FIELD => member.loadout.join(", "),
+        _ => member.conditions.iter()
+            .map(|condition| match condition_description(condition) {
+                Some(description) => format!("{} ({})", condition, description),
+                None => condition.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
 }
 
-impl CrewMember {
-    pub fn new(name: String, picture: String, role: String) -> CrewMember {
-        CrewMember {
-            name,
-            picture,
-            role,
-            location: String::from("???"),
-            vitality: 1,
-        }
+fn adjust_field(member: &mut CrewMember, field: usize, step: i8) {
+    let percentage = |value: &mut u8| *value = value.saturating_add_signed(step).min(MAX_STAT);
+    match field {
+        0 => {
+            let index = Class::ALL.iter().position(|class| *class == member.class).unwrap_or(0);
+            let count = Class::ALL.len();
+            member.class = Class::ALL[(index + count).wrapping_add_signed(step as isize) % count];
+        },
+        1 => percentage(&mut member.stats.strength),
+        2 => percentage(&mut member.stats.speed),
+        3 => percentage(&mut member.stats.intellect),
+        4 => percentage(&mut member.stats.combat),
+        5 => percentage(&mut member.saves.sanity),
+        6 => percentage(&mut member.saves.fear),
+        7 => percentage(&mut member.saves.body),
+        8 => member.health = member.health.saturating_add_signed(step).min(member.max_health),
+        9 => {
+            member.max_health = member.max_health.saturating_add_signed(step).max(1);
+            member.health = member.health.min(member.max_health);
+        },
+        10 => member.wounds = member.wounds.saturating_add_signed(step).min(member.max_wounds),
+        11 => {
+            member.max_wounds = member.max_wounds.saturating_add_signed(step).max(1);
+            member.wounds = member.wounds.min(member.max_wounds);
+        },
+        12 => member.stress = member.stress.saturating_add_signed(step).min(MAX_STRESS),
+        _ => {},
     }
 }
 
 impl Widget for &CrewMember {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        // let [left, right] = Layout::horizontal([
-        //     Constraint::Percentage(50),
-        //     Constraint::Percentage(50),
-        // ])
-        //     .areas(area);
-
-        // let info = Paragraph::new(self.name.clone());
-        // info.render(left, buf);
- 
-        let picture = Paragraph::new(self.picture.clone());
-        picture.render(area, buf);
-
         let block = Block::bordered()
+            .title(format!(" {} ", self.role))
             .title_bottom(self.name.clone().bold())
             .title_alignment(Alignment::Center)
             .border_set(border::PLAIN);
-        
+        let inner = block.inner(area);
         block.render(area, buf);
+
+        let [picture, status] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ]).areas(inner);
+
+        Paragraph::new(self.portrait.art()).render(picture, buf);
+
+        let health = format!("Gezondheid {}/{}", self.health, self.max_health);
+        let health = match self.health {
+            _ if !self.is_active() => "Uitgeschakeld".red().bold(),
+            h if h <= self.max_health / 3 => health.red(),
+            h if h < self.max_health => health.yellow(),
+            _ => health.green(),
+        };
+        let wounds = format!(" - Wonden {}/{}", self.wounds, self.max_wounds);
+        let wounds = if self.wounds > 0 { wounds.red() } else { wounds.into() };
+        let mut status_line = vec![format!("{} - {} - ", self.location, self.class).into(), health, wounds];
+        if let Some(condition) = self.conditions.last() {
+            status_line.push(format!(" - {}", condition).magenta().bold());
+        }
+        Line::from(status_line)
+            .alignment(Alignment::Center)
+            .render(status, buf);
+    }
+}
+
+/// Cards of the crew, two per row
+#[derive(Default)]
+pub struct CrewStatus {
+    selected: usize,
+    /// First visible row
+    scroll: usize,
+    /// Character sheet of the selected crew member is open
+    details: bool,
+    selected_field: usize,
+    /// Skill or item being typed, added to the selected list on Enter
+    new_entry: Option<String>,
+    /// Dice roller for the selected crew member, when it is open
+    dice: Option<DiceRoller>,
+}
+
+impl CrewStatus {
+    /// Whether keys should go to this widget only, instead of the menu as well
+    pub fn captures_input(&self) -> bool {
+        self.details || self.dice.is_some()
+    }
+
+    pub fn handle_press_event(&mut self, key_event: KeyEvent, crew: &mut [CrewMember]) -> Vec<Event> {
+        if let Some(dice) = &mut self.dice {
+            let Some(member) = crew.get_mut(self.selected) else {
+                self.dice = None;
+                return Vec::new();
+            };
+            if !dice.is_typing() && matches!(key_event.code, KeyCode::Char('r') | KeyCode::Esc) {
+                self.dice = None;
+                return Vec::new();
+            }
+            return dice.handle_press_event(key_event, member).map(Event::Log).into_iter().collect();
+        }
+        if self.details {
+            let edited = match crew.get_mut(self.selected) {
+                Some(member) => self.handle_details(key_event, member),
+                None => { self.details = false; false },
+            };
+            return if edited { vec![Event::CrewUpdate] } else { Vec::new() };
+        }
+        let last = crew.len().saturating_sub(1);
+        match key_event.code {
+            KeyCode::Char('i') => { self.details = !crew.is_empty(); },
+            KeyCode::Char('r') if !crew.is_empty() => { self.dice = Some(DiceRoller::default()); },
+            KeyCode::Char('a') => { self.selected = self.selected.saturating_sub(1); },
+            KeyCode::Char('d') => { self.selected = (self.selected + 1).min(last); },
+            KeyCode::Char('w') => { self.selected = self.selected.saturating_sub(2); },
+            KeyCode::Char('s') => { self.selected = (self.selected + 2).min(last); },
+            _ => {},
+        }
+
+        // Keep the selected card on screen
+        let row = self.selected / 2;
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + VISIBLE_ROWS {
+            self.scroll = row + 1 - VISIBLE_ROWS;
+        }
+        Vec::new()
+    }
+
+    fn handle_details(&mut self, key_event: KeyEvent, member: &mut CrewMember) -> bool {
+        let list = match self.selected_field {
+            SKILLS_FIELD => Some(&mut member.skills),
+            LOADOUT_FIELD => Some(&mut member.loadout),
+            CONDITIONS_FIELD => Some(&mut member.conditions),
+            _ => None,
+        };
+        if let Some(mut entry) = self.new_entry.take() {
+            match key_event.code {
+                KeyCode::Char(c) => { entry.push(c); },
+                KeyCode::Backspace => { entry.pop(); },
+                KeyCode::Enter => {
+                    if let (Some(list), false) = (list, entry.trim().is_empty()) {
+                        list.push(String::from(entry.trim()));
+                        return true;
+                    }
+                    return false;
+                },
+                KeyCode::Esc => { return false; },
+                _ => {},
+            }
+            self.new_entry = Some(entry);
+            return false;
+        }
+
+        let step = match key_event.code {
+            KeyCode::Char('i') | KeyCode::Esc => { self.details = false; 0 },
+            KeyCode::Char('w') => { self.selected_field = (self.selected_field + FIELDS.len() - 1) % FIELDS.len(); 0 },
+            KeyCode::Char('s') => { self.selected_field = (self.selected_field + 1) % FIELDS.len(); 0 },
+            KeyCode::Char('+') | KeyCode::Char('d') => 1,
+            KeyCode::Char('-') | KeyCode::Char('a') => -1,
+            KeyCode::Char('n') if list.is_some() => { self.new_entry = Some(String::new()); 0 },
+            KeyCode::Char('x') => {
+                return list.and_then(|list| list.pop()).is_some();
+            },
+            _ => 0,
+        };
+        if step == 0 {
+            return false;
+        }
+        adjust_field(member, self.selected_field, step);
+        true
     }
 }
 
-pub struct CrewStatus;
+pub struct CrewView<'a> {
+    pub status: &'a CrewStatus,
+    pub crew: &'a [CrewMember],
+}
+
+impl CrewView<'_> {
+    fn render_details(&self, area: Rect, buf: &mut Buffer, member: &CrewMember) {
+        let [card, sheet] = Layout::horizontal([
+            Constraint::Percentage(40),
+            Constraint::Percentage(60),
+        ]).areas(area);
+        member.render(card, buf);
+
+        let rows: Vec<Row> = FIELDS.iter().enumerate().map(|(i, label)| {
+            let style = match i == self.status.selected_field {
+                true => Style::default().fg(Color::Green).bold(),
+                false => Style::default(),
+            };
+            Row::new(vec![Cell::from(*label), Cell::from(field_value(member, i))]).style(style)
+        }).collect();
 
-impl Widget for &CrewStatus {
+        let instructions = match &self.status.new_entry {
+            Some(entry) => Line::from(vec![
+                format!(" Nieuw: {}_ ", entry).into(),
+                " Toevoegen ".into(), "<Enter>".green().bold(),
+                " Annuleer ".into(), "<Esc> ".green().bold(),
+            ]),
+            None => Line::from(vec![
+                " Kies ".into(), "<w/s>".green().bold(),
+                " Aanpassen ".into(), "<+/->".green().bold(),
+                " Toevoegen ".into(), "<n>".green().bold(),
+                " Verwijderen/wissen ".into(), "<x>".green().bold(),
+                " Sluiten ".into(), "<i> ".green().bold(),
+            ]),
+        };
+        let block = Block::bordered()
+            .title(format!(" {} ", member.name).bold())
+            .title_bottom(instructions)
+            .title_alignment(Alignment::Center);
+        let table = Table::new(rows, [Constraint::Length(16), Constraint::Min(0)]).block(block);
+        Widget::render(table, sheet, buf);
+    }
+}
+
+impl Widget for CrewView<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let [col_left, col_right ]= Layout::horizontal([
+        if self.crew.is_empty() {
+            Paragraph::new("Er is geen crew aan boord").centered().render(area, buf);
+            return;
+        }
+        if self.status.details {
+            if let Some(member) = self.crew.get(self.status.selected) {
+                self.render_details(area, buf, member);
+                return;
+            }
+        }
+
+        let [cards, scrollbar] = Layout::horizontal([
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ]).areas(area);
+        let [col_left, col_right] = Layout::horizontal([
             Constraint::Percentage(50),
             Constraint::Percentage(50),
-        ]).areas(area);
-
-        let cards = Layout::vertical([
-            Constraint::Ratio(1, 3) ; 3
+        ]).areas(cards);
+        let rows = Layout::vertical([
+            Constraint::Ratio(1, VISIBLE_ROWS as u32) ; VISIBLE_ROWS
         ]);
-        let [top_left, mid_left, bot_left] = cards.areas(col_left);
-        let [top_right, mid_right, bot_right] = cards.areas(col_right);
-
-        CrewMember::new(String::from("Yoda"), String::from(YODA), String::new()).render(top_left, buf);
-        CrewMember::new(String::from("Jack Skellington"), String::from(SKELETON), String::new()).render(top_right, buf);
-        CrewMember::new(String::from("???"), String::from(XENOMORPH), String::new()).render(mid_left, buf);
-        CrewMember::new(String::from("Bob"), String::from(BOB), String::new()).render(mid_right, buf);
-        CrewMember::new(String::from("Yabooiiii"), String::from(GUY), String::new()).render(bot_left, buf);
-        CrewMember::new(String::from("Ally"), String::from(ALIEN), String::new()).render(bot_right, buf);
-    }   
-}
+        let left: [Rect; VISIBLE_ROWS] = rows.areas(col_left);
+        let right: [Rect; VISIBLE_ROWS] = rows.areas(col_right);
+
+        let first = self.status.scroll * 2;
+        for (i, member) in self.crew.iter().enumerate().skip(first).take(VISIBLE_ROWS * 2) {
+            let row = (i - first) / 2;
+            let card = if i % 2 == 0 { left[row] } else { right[row] };
+            member.render(card, buf);
+            if i == self.status.selected {
+                Block::bordered()
+                    .border_set(border::THICK)
+                    .border_style(Style::default().fg(Color::Green))
+                    .title(format!(" {} ", member.role))
+                    .title_bottom(Line::from(vec![
+                        member.name.clone().bold(),
+                        " Details ".into(), "<i>".green().bold(),
+                        " Dobbelstenen ".into(), "<r>".green().bold(),
+                    ]))
+                    .title_alignment(Alignment::Center)
+                    .render(card, buf);
+            }
+        }
+
+        let total_rows = self.crew.len().div_ceil(2);
+        if total_rows > VISIBLE_ROWS {
+            let mut state = ScrollbarState::new(total_rows - VISIBLE_ROWS + 1).position(self.status.scroll);
+            StatefulWidget::render(Scrollbar::new(ScrollbarOrientation::VerticalRight), scrollbar, buf, &mut state);
+        }
 
+        if let (Some(roller), Some(member)) = (&self.status.dice, self.crew.get(self.status.selected)) {
+            DiceView { roller, member }.render(area, buf);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent}, prelude::*, widgets::{Block, BorderType, Cell, Clear, Paragraph, Row, Table, Widget, Wrap}
+};
+
+use crate::{crew::{Attribute, CrewMember, PanicCheck}, dice::{Check, CheckOutcome, Dice, DiceRoll, RollMode}, logbook::LogKind, util};
+
+/// How long the dice keep tumbling before the result shows
+const ROLL_ANIMATION: Duration = Duration::from_millis(800);
+
+enum RollResult {
+    Check(Check),
+    Dice(DiceRoll),
+    Panic(PanicCheck),
+}
+
+/// Panel for rolling checks against the stats of a crew member, or any dice expression
+pub struct DiceRoller {
+    attribute: usize,
+    mode: RollMode,
+    /// Dice expression being typed
+    expression: Option<String>,
+    result: Option<RollResult>,
+    rolled_at: Instant,
+    error: Option<String>,
+}
+
+impl Default for DiceRoller {
+    fn default() -> Self {
+        DiceRoller {
+            attribute: 0,
+            mode: RollMode::Normal,
+            expression: None,
+            result: None,
+            rolled_at: Instant::now(),
+            error: None,
+        }
+    }
+}
+
+impl DiceRoller {
+    /// Esc should stop typing instead of closing the panel
+    pub fn is_typing(&self) -> bool {
+        self.expression.is_some()
+    }
+
+    /// Returns the logbook entry of a roll, failed saves and panic checks change the crew member
+    pub fn handle_press_event(&mut self, key_event: KeyEvent, member: &mut CrewMember) -> Option<LogKind> {
+        let mut rng = rand::thread_rng();
+        if let Some(mut expression) = self.expression.take() {
+            match key_event.code {
+                KeyCode::Char(c) => { expression.push(c); },
+                KeyCode::Backspace => { expression.pop(); },
+                KeyCode::Enter => {
+                    return match expression.parse::<Dice>() {
+                        Ok(dice) => {
+                            self.error = None;
+                            let roll = dice.roll(&mut rng);
+                            self.rolled(RollResult::Dice(roll.clone()));
+                            Some(LogKind::Roll { name: member.name.clone(), roll })
+                        },
+                        Err(err) => {
+                            self.error = Some(err.to_string());
+                            None
+                        },
+                    };
+                },
+                KeyCode::Esc => { return None; },
+                _ => {},
+            }
+            self.expression = Some(expression);
+            return None;
+        }
+
+        let count = Attribute::ALL.len();
+        match key_event.code {
+            KeyCode::Char('w') => { self.attribute = (self.attribute + count - 1) % count; },
+            KeyCode::Char('s') => { self.attribute = (self.attribute + 1) % count; },
+            KeyCode::Char('+') => { self.mode = if self.mode == RollMode::Advantage { RollMode::Normal } else { RollMode::Advantage }; },
+            KeyCode::Char('-') => { self.mode = if self.mode == RollMode::Disadvantage { RollMode::Normal } else { RollMode::Disadvantage }; },
+            KeyCode::Char('m') => { self.mode = self.mode.next(); },
+            KeyCode::Char('t') => { self.expression = Some(String::new()); },
+            KeyCode::Char('p') => {
+                let check = member.panic_check(&mut rng);
+                let kind = check.log(&member.name);
+                self.rolled(RollResult::Panic(check));
+                return Some(kind);
+            },
+            KeyCode::Enter => {
+                let attribute = Attribute::ALL[self.attribute];
+                let check = Check::roll(&mut rng, member.attribute(attribute), self.mode);
+                if attribute.is_save() && !check.is_success() {
+                    member.add_stress(1);
+                }
+                self.rolled(RollResult::Check(check.clone()));
+                return Some(LogKind::Check { name: member.name.clone(), attribute, check });
+            },
+            _ => {},
+        }
+        None
+    }
+
+    fn rolled(&mut self, result: RollResult) {
+        self.result = Some(result);
+        self.rolled_at = Instant::now();
+    }
+}
+
+pub struct DiceView<'a> {
+    pub roller: &'a DiceRoller,
+    pub member: &'a CrewMember,
+}
+
+impl DiceView<'_> {
+    fn result_lines(&self) -> Vec<Line<'static>> {
+        let Some(result) = &self.roller.result else {
+            return vec![Line::from("Nog niet gerold".dark_gray())];
+        };
+        // Tumbling dice
+        if self.roller.rolled_at.elapsed() < ROLL_ANIMATION {
+            let mut rng = rand::thread_rng();
+            let tumbling = match result {
+                RollResult::Check(check) => check.rolls.iter().map(|_| format!("{:02}", rng.gen_range(0..100))).collect::<Vec<_>>(),
+                RollResult::Dice(roll) => roll.rolls.iter().map(|_| rng.gen_range(1..=roll.dice.sides).to_string()).collect(),
+                RollResult::Panic(_) => vec![rng.gen_range(1..=20).to_string()],
+            };
+            return vec![Line::from(format!("[ {} ]", tumbling.join(" ")).yellow().bold())];
+        }
+
+        match result {
+            RollResult::Check(check) => {
+                let rolls: Vec<String> = check.rolls.iter().map(|roll| format!("{:02}", roll)).collect();
+                let outcome = check.outcome.to_string().to_uppercase();
+                let outcome = match check.outcome {
+                    CheckOutcome::CriticalSuccess => outcome.green().bold().reversed(),
+                    CheckOutcome::Success => outcome.green().bold(),
+                    CheckOutcome::Failure => outcome.red().bold(),
+                    CheckOutcome::CriticalFailure => outcome.red().bold().reversed(),
+                };
+                vec![
+                    Line::from(format!("[ {} ] onder {}{}", rolls.join(" "), check.target, check.mode)),
+                    Line::from(format!("Telt: {:02}", check.roll)),
+                    Line::from(outcome),
+                ]
+            },
+            RollResult::Dice(roll) => {
+                let rolls: Vec<String> = roll.rolls.iter().map(|roll| roll.to_string()).collect();
+                vec![
+                    Line::from(format!("{}: [ {} ]", roll.dice, rolls.join(" "))),
+                    Line::from(format!("Totaal: {}", roll.total).yellow().bold()),
+                ]
+            },
+            RollResult::Panic(check) => {
+                let mut lines = vec![Line::from(format!("d20: [ {} ] tegen {} stress", check.roll, check.stress))];
+                match check.effect {
+                    Some(effect) => {
+                        lines.push(Line::from(format!("PANIEK: {}", effect.name).red().bold()));
+                        lines.push(Line::from(effect.description));
+                    },
+                    None => lines.push(Line::from("BLIJFT KALM".green().bold())),
+                }
+                lines
+            },
+        }
+    }
+}
+
+impl Widget for DiceView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = util::center(area, Constraint::Percentage(60), Constraint::Length(Attribute::ALL.len() as u16 + 11));
+        Clear.render(area, buf);
+
+        let instructions = match self.roller.is_typing() {
+            true => Line::from(vec![" Rol ".into(), "<Enter>".green().bold(), " Annuleer ".into(), "<Esc> ".green().bold()]),
+            false => Line::from(vec![
+                " Kies ".into(), "<w/s>".green().bold(),
+                " [+]/[-] ".into(), "<+/->".green().bold(),
+                " Rol ".into(), "<Enter>".green().bold(),
+                " Schade ".into(), "<t>".green().bold(),
+                " Paniek ".into(), "<p>".green().bold(),
+                " Sluiten ".into(), "<r> ".green().bold(),
+            ]),
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Double)
+            .title(format!(" Dobbelstenen - {} ", self.member.name).bold())
+            .title_bottom(instructions)
+            .title_alignment(Alignment::Center);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [checks, result] = Layout::horizontal([
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ]).areas(inner);
+
+        let rows: Vec<Row> = Attribute::ALL.iter().enumerate().map(|(i, attribute)| {
+            let style = match i == self.roller.attribute {
+                true => Style::default().fg(Color::Green).bold(),
+                false => Style::default(),
+            };
+            Row::new(vec![Cell::from(attribute.to_string()), Cell::from(self.member.attribute(*attribute).to_string())]).style(style)
+        }).collect();
+        let mode = match self.roller.mode {
+            RollMode::Normal => String::from("normaal"),
+            mode => mode.to_string(),
+        };
+        let table = Table::new(rows, [Constraint::Length(10), Constraint::Min(0)])
+            .block(Block::bordered().title(format!(" d100 - {} ", mode.trim())));
+        Widget::render(table, checks, buf);
+
+        let mut lines = self.result_lines();
+        lines.push(Line::from(""));
+        match (&self.roller.expression, &self.roller.error) {
+            (Some(expression), _) => lines.push(Line::from(format!("Worp: {}_", expression))),
+            (None, Some(error)) => lines.push(Line::from(error.clone().red())),
+            (None, None) => {},
+        }
+        Paragraph::new(lines)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(format!(" Resultaat - stress {} ", self.member.stress)))
+            .render(result, buf);
+    }
+}
@@ -0,0 +1,95 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent}, prelude::*, widgets::{Block, BorderType, Clear, Paragraph, Widget, Wrap}
+};
+
+use crate::{events::{Choice, EventDefinition}, util};
+
+/// Modal for resolving a random event, it takes all input until it is closed
+pub struct EventPopup {
+    definition: EventDefinition,
+    selected: usize,
+    resolved: Option<usize>,
+    pub closed: bool,
+}
+
+impl EventPopup {
+    pub fn new(definition: EventDefinition) -> Self {
+        EventPopup {
+            definition,
+            selected: 0,
+            resolved: None,
+            closed: false,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.definition.title
+    }
+
+    /// Returns the choice once it has been made
+    pub fn handle_press_event(&mut self, key_event: KeyEvent) -> Option<Choice> {
+        let choices = self.definition.choices.len();
+        if self.resolved.is_some() || choices == 0 {
+            if key_event.code == KeyCode::Enter {
+                self.closed = true;
+            }
+            return None;
+        }
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('w') => { self.selected = (self.selected + choices - 1) % choices; },
+            KeyCode::Down | KeyCode::Char('s') => { self.selected = (self.selected + 1) % choices; },
+            KeyCode::Enter => {
+                self.resolved = Some(self.selected);
+                return Some(self.definition.choices[self.selected].clone());
+            },
+            _ => {},
+        }
+        None
+    }
+}
+
+impl Widget for &EventPopup {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(self.definition.text.clone()),
+            Line::from(""),
+        ];
+        match self.resolved {
+            Some(i) => {
+                let choice = &self.definition.choices[i];
+                lines.push(Line::from(format!("> {}", choice.label).green().bold()));
+                lines.push(Line::from(""));
+                lines.push(Line::from(choice.result.clone()));
+                for outcome in &choice.outcomes {
+                    lines.push(Line::from(outcome.to_string().yellow()));
+                }
+            },
+            None => {
+                for (i, choice) in self.definition.choices.iter().enumerate() {
+                    let line = match i == self.selected {
+                        true => Line::from(format!("> {}", choice.label).green().bold()),
+                        false => Line::from(format!("  {}", choice.label)),
+                    };
+                    lines.push(line);
+                }
+            },
+        }
+
+        let instructions = match self.resolved.is_some() || self.definition.choices.is_empty() {
+            true => Line::from(vec![" Sluiten ".into(), "<Enter> ".green().bold()]),
+            false => Line::from(vec![" Kies ".into(), "<w/s>".green().bold(), " Bevestig ".into(), "<Enter> ".green().bold()]),
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Double)
+            .title(format!(" {} ", self.definition.title).red().bold())
+            .title_bottom(instructions)
+            .title_alignment(Alignment::Center);
+
+        let area = util::center(area, Constraint::Percentage(70), Constraint::Length(lines.len() as u16 + 6));
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(block)
+            .render(area, buf);
+    }
+}
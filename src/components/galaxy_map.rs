@@ -1,40 +1,310 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
+use rand::Rng;
 use ratatui::{
     crossterm::event::{KeyCode, KeyEvent}, prelude::*, widgets::{
         canvas::{
-            Canvas, Circle, Rectangle,
-        }, Block, Gauge, Widget
+            Canvas, Circle, Line as CanvasLine, Rectangle,
+        }, Block, Clear, Gauge, List, ListState, StatefulWidget, Widget
     }
 };
 
-use crate::{objects::SolarSystem, util::{within_radius, Event, ItemDiff, WARP_HOLD_DURATION}};
+use crate::{events::Marker, logbook::LogKind, objects::{SignalReading, SolarSystem}, route::{plan_route, Route, RouteError}, user::Ship, util::{distance, within_radius, Event, ItemDiff, WARP_HOLD_DURATION}};
 
 const MOVE_DISTANCE: f64 = 0.1;
 const STAR_DISTANCE: f64 = MOVE_DISTANCE * 2.5;
-const WARP_DISTANCE: f64 = MOVE_DISTANCE * 30.0;
+/// Width of the view that the cursor steps and symbol sizes are tuned for
+const BASE_VIEW_SIZE: f64 = 30.0;
+const MIN_VIEW_SIZE: f64 = 2.0;
+const ZOOM_FACTOR: f64 = 1.5;
 
 pub struct GalacticMap {
     pub solar_systems: Vec<SolarSystem>,
+    pub markers: Vec<Marker>,
     pub current_system: Option<usize>,
     pub current_pos: (f64, f64),
     selected_pos: (f64, f64),
+    /// System the cursor snapped to, while cycling through the reachable systems
+    snapped: Option<usize>,
     warp_progress: f64,
     warped: bool,
+    // Copied from the user, to know how far and if we can warp
+    pub ship: Ship,
+    pub fuel: i32,
+    /// Crew members are still down on a planet, the ship can not leave without them
+    pub away_team: bool,
+    /// Names of the visited systems, everything outside their sensor range stays dark
+    pub discovered: HashSet<String>,
+    /// Readings of the last sensor sweep
+    pub signals: Vec<SignalReading>,
+
+    // Route planner
+    route: Option<Route>,
+    route_message: Option<String>,
+    /// Selected entry of the system list, when it is open
+    system_list: Option<usize>,
+
+    // Viewport
+    view_center: (f64, f64),
+    /// Width and height of the visible part of the galaxy
+    view_size: f64,
+    show_labels: bool,
+    /// Text typed into the system search, when it is open
+    search: Option<String>,
 }
 
 impl GalacticMap {
-    pub fn new(solar_systems: Vec<SolarSystem>, pos: (f64, f64)) -> Self {
-        GalacticMap {
+    pub fn new(solar_systems: Vec<SolarSystem>, markers: Vec<Marker>, discovered: HashSet<String>, pos: (f64, f64)) -> Self {
+        let mut map = GalacticMap {
             solar_systems,
+            markers,
             current_system: None,
             current_pos: pos,
             selected_pos: pos,
+            snapped: None,
             warp_progress: 0.0,
             warped: false,
+            ship: Ship::default(),
+            fuel: 0,
+            away_team: false,
+            discovered,
+            signals: Vec::new(),
+            route: None,
+            route_message: None,
+            system_list: None,
+            view_center: pos,
+            view_size: BASE_VIEW_SIZE,
+            show_labels: true,
+            search: None,
+        };
+        map.reset_view();
+        map
+    }
+
+    /// Whether keys should go to this widget only, instead of the menu as well
+    pub fn captures_input(&self) -> bool {
+        self.system_list.is_some() || self.search.is_some()
+    }
+
+    /// Lower left and upper right corner of everything on the map, with some room around it
+    fn galaxy_bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let margin = 1.0;
+        let points = self.solar_systems.iter().map(|system| system.pos).chain([self.current_pos]);
+        let (mut min, mut max) = (self.current_pos, self.current_pos);
+        for (x, y) in points {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        ((min.0 - margin, min.1 - margin), (max.0 + margin, max.1 + margin))
+    }
+
+    /// Random spot within the systems of the galaxy
+    pub fn random_position(&self, rng: &mut impl Rng) -> (f64, f64) {
+        let (min, max) = self.galaxy_bounds();
+        (rng.gen_range(min.0..=max.0), rng.gen_range(min.1..=max.1))
+    }
+
+    /// Zoom out to show the whole galaxy
+    fn reset_view(&mut self) {
+        let (min, max) = self.galaxy_bounds();
+        self.view_center = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+        self.view_size = (max.0 - min.0).max(max.1 - min.1).max(MIN_VIEW_SIZE);
+    }
+
+    fn zoom(&mut self, factor: f64) {
+        let (min, max) = self.galaxy_bounds();
+        let max_size = (max.0 - min.0).max(max.1 - min.1).max(MIN_VIEW_SIZE);
+        self.view_size = (self.view_size * factor).clamp(MIN_VIEW_SIZE, max_size);
+        self.follow_cursor();
+    }
+
+    /// Pan the view so the cursor stays on screen
+    fn follow_cursor(&mut self) {
+        let half = self.view_size / 2.0;
+        let margin = self.view_size * 0.1;
+        let (x, y) = self.selected_pos;
+        if x < self.view_center.0 - half + margin {
+            self.view_center.0 = x + half - margin;
+        } else if x > self.view_center.0 + half - margin {
+            self.view_center.0 = x - half + margin;
+        }
+        if y < self.view_center.1 - half + margin {
+            self.view_center.1 = y + half - margin;
+        } else if y > self.view_center.1 + half - margin {
+            self.view_center.1 = y - half + margin;
+        }
+    }
+
+    /// Symbols and cursor steps grow with the view, so they look the same at every zoom
+    fn scale(&self) -> f64 {
+        self.view_size / BASE_VIEW_SIZE
+    }
+
+    /// A cursor step never jumps over a system, however far the view is zoomed out
+    fn selection_radius(&self) -> f64 {
+        STAR_DISTANCE.max(MOVE_DISTANCE * self.scale())
+    }
+
+    /// Warps to the system under the cursor land on it, so the ship arrives there
+    fn warp_target(&self) -> (f64, f64) {
+        self.selected_system().map_or(self.selected_pos, |system| system.pos)
+    }
+
+    /// Visible systems within warp range, closest first
+    fn reachable_systems(&self) -> Vec<usize> {
+        self.systems_by_distance().into_iter()
+            .filter(|i| Some(*i) != self.current_system)
+            .filter(|i| within_radius(self.current_pos, self.solar_systems[*i].pos, self.ship.warp_range))
+            .collect()
+    }
+
+    /// Put the cursor on the next or previous reachable system
+    fn snap(&mut self, step: isize) {
+        let reachable = self.reachable_systems();
+        if reachable.is_empty() {
+            self.snapped = None;
+            return;
+        }
+        let count = reachable.len() as isize;
+        let next = match self.snapped.and_then(|snapped| reachable.iter().position(|i| *i == snapped)) {
+            Some(current) => (current as isize + step).rem_euclid(count),
+            None if step > 0 => 0,
+            None => count - 1,
+        };
+        let system = reachable[next as usize];
+        self.snapped = Some(system);
+        self.selected_pos = self.solar_systems[system].pos;
+    }
+
+    /// Only visited systems are known by name, the rest is a blip on the sensors
+    fn display_name(&self, system: &SolarSystem) -> String {
+        match self.discovered.contains(&system.name) {
+            true => system.name.clone(),
+            false => String::from("Onbekend signaal"),
+        }
+    }
+
+    /// Visible system under the cursor, the closest one when zoomed out far enough to cover several
+    fn selected_system(&self) -> Option<&SolarSystem> {
+        self.solar_systems.iter()
+            .filter(|system| within_radius(self.selected_pos, system.pos, self.selection_radius()))
+            .filter(|system| self.is_visible(system))
+            .min_by(|a, b| distance(self.selected_pos, a.pos).total_cmp(&distance(self.selected_pos, b.pos)))
+    }
+
+    /// Visited systems with the search text in their name, unknown signals have no name to search for
+    fn search_results(&self, text: &str) -> Vec<usize> {
+        let text = text.to_lowercase();
+        self.systems_by_distance().into_iter()
+            .filter(|i| self.discovered.contains(&self.solar_systems[*i].name))
+            .filter(|i| self.solar_systems[*i].name.to_lowercase().contains(&text))
+            .collect()
+    }
+
+    fn handle_search(&mut self, key_event: KeyEvent, mut text: String) {
+        match key_event.code {
+            KeyCode::Char(c) => { text.push(c); },
+            KeyCode::Backspace => { text.pop(); },
+            KeyCode::Enter => {
+                if let Some(i) = self.search_results(&text).first() {
+                    self.selected_pos = self.solar_systems[*i].pos;
+                    self.view_center = self.selected_pos;
+                }
+                self.search = None;
+                return;
+            },
+            KeyCode::Esc => {
+                self.search = None;
+                return;
+            },
+            _ => {},
+        }
+        self.search = Some(text);
+    }
+
+    /// Whether a system has been picked up by the sensors, from here or from a visited system
+    pub fn is_visible(&self, system: &SolarSystem) -> bool {
+        let range = self.ship.sensor_range;
+        self.discovered.contains(&system.name)
+            || within_radius(self.current_pos, system.pos, range)
+            || self.solar_systems.iter()
+                .filter(|other| self.discovered.contains(&other.name))
+                .any(|other| within_radius(other.pos, system.pos, range))
+    }
+
+    /// Show a message in the route line until the next route is planned
+    pub fn notify(&mut self, message: String) {
+        self.route_message = Some(message);
+    }
+
+    /// Scan every system within the sweep radius and log what was found
+    pub fn sweep(&mut self) -> LogKind {
+        let mut rng = rand::thread_rng();
+        let radius = self.ship.sweep_radius();
+        self.signals = self.solar_systems.iter()
+            .filter(|system| within_radius(self.current_pos, system.pos, radius))
+            .filter_map(|system| system.signal(&mut rng).map(|signal| SignalReading { pos: system.pos, signal }))
+            .collect();
+        LogKind::Sweep { pos: self.current_pos, readings: self.signals.clone() }
+    }
+
+    fn visibility(&self) -> Vec<bool> {
+        self.solar_systems.iter().map(|system| self.is_visible(system)).collect()
+    }
+
+    /// All visible solar systems, closest first
+    fn systems_by_distance(&self) -> Vec<usize> {
+        let visible = self.visibility();
+        let mut systems: Vec<usize> = (0..self.solar_systems.len()).filter(|i| visible[*i]).collect();
+        systems.sort_by(|a, b| {
+            distance(self.current_pos, self.solar_systems[*a].pos)
+                .total_cmp(&distance(self.current_pos, self.solar_systems[*b].pos))
+        });
+        systems
+    }
+
+    fn plan_route(&mut self, target: usize) {
+        let name = self.display_name(&self.solar_systems[target]);
+        match plan_route(&self.solar_systems, &self.visibility(), self.current_pos, target, &self.ship, self.fuel) {
+            Ok(route) => {
+                self.route_message = Some(format!("Route naar {}: {} sprongen, {} brandstof", name, route.jumps.len(), route.fuel));
+                self.route = Some(route);
+            },
+            Err(RouteError::Unreachable) => {
+                self.route_message = Some(format!("{} is niet te bereiken met dit warp bereik", name));
+                self.route = None;
+            },
+            Err(RouteError::NotEnoughFuel(fuel)) => {
+                self.route_message = Some(format!("Route naar {} kost {} brandstof, te weinig brandstof", name, fuel));
+                self.route = None;
+            },
+        }
+    }
+
+    fn handle_system_list(&mut self, key_event: KeyEvent, selected: usize) {
+        let count = self.systems_by_distance().len().max(1);
+        match key_event.code {
+            KeyCode::Char('w') => { self.system_list = Some((selected + count - 1) % count); },
+            KeyCode::Char('s') => { self.system_list = Some((selected + 1) % count); },
+            KeyCode::Enter => {
+                self.system_list = None;
+                if let Some(target) = self.systems_by_distance().get(selected) {
+                    self.plan_route(*target);
+                }
+            },
+            KeyCode::Esc | KeyCode::Char('r') => { self.system_list = None; },
+            _ => {},
         }
     }
 
+    fn warp_cost(&self) -> i32 {
+        self.ship.fuel_cost(distance(self.current_pos, self.warp_target()))
+    }
+
+    fn can_warp(&self) -> bool {
+        within_radius(self.warp_target(), self.current_pos, self.ship.warp_range) && self.fuel >= self.warp_cost() && !self.away_team
+    }
+
     pub fn get_current_system(&self) -> Option<&SolarSystem> {
         if let Some(current) = self.current_system {
             Some(&self.solar_systems[current])
@@ -51,18 +321,59 @@ impl GalacticMap {
         }
     }
 
-    pub fn handle_press_event(&mut self, key_event: KeyEvent, last_key_pressed: Option<KeyEvent>, last_press_time: std::time::Instant, can_warp: bool) -> Vec<Event> {
+    pub fn handle_press_event(&mut self, key_event: KeyEvent, last_key_pressed: Option<KeyEvent>, last_press_time: std::time::Instant) -> Vec<Event> {
+        if let Some(selected) = self.system_list {
+            self.handle_system_list(key_event, selected);
+            return Vec::new();
+        }
+        if let Some(text) = self.search.take() {
+            self.handle_search(key_event, text);
+            return Vec::new();
+        }
         if key_event.code != KeyCode::Enter {
             self.warped = false;
         }
         match key_event.code {
-            KeyCode::Char('a') => { self.selected_pos.0 -= MOVE_DISTANCE; },
-            KeyCode::Char('d') => { self.selected_pos.0 += MOVE_DISTANCE; },
-            KeyCode::Char('w') => { self.selected_pos.1 += MOVE_DISTANCE; },
-            KeyCode::Char('s') => { self.selected_pos.1 -= MOVE_DISTANCE; },
+            KeyCode::Char('r') => { self.system_list = Some(0); },
+            KeyCode::Char('p') => {
+                // Plan to the visible system closest to the cursor
+                let closest = self.systems_by_distance().into_iter().min_by(|a, b| {
+                    distance(self.selected_pos, self.solar_systems[*a].pos)
+                        .total_cmp(&distance(self.selected_pos, self.solar_systems[*b].pos))
+                });
+                if let Some(target) = closest {
+                    self.plan_route(target);
+                }
+            },
+            KeyCode::Char('n') => {
+                if let Some(next) = self.route.as_ref().and_then(|route| route.jumps.first()) {
+                    self.selected_pos = self.solar_systems[*next].pos;
+                }
+            },
+            KeyCode::Char('c') => {
+                self.route = None;
+                self.route_message = None;
+            },
+            // Free cursor
+            KeyCode::Char('a') => { self.selected_pos.0 -= MOVE_DISTANCE * self.scale(); self.snapped = None; },
+            KeyCode::Char('d') => { self.selected_pos.0 += MOVE_DISTANCE * self.scale(); self.snapped = None; },
+            KeyCode::Char('w') => { self.selected_pos.1 += MOVE_DISTANCE * self.scale(); self.snapped = None; },
+            KeyCode::Char('s') => { self.selected_pos.1 -= MOVE_DISTANCE * self.scale(); self.snapped = None; },
+            // Snap to the reachable systems
+            KeyCode::Tab | KeyCode::Right => { self.snap(1); },
+            KeyCode::BackTab | KeyCode::Left => { self.snap(-1); },
+            KeyCode::Char('+') | KeyCode::Char('=') => { self.zoom(1.0 / ZOOM_FACTOR); },
+            KeyCode::Char('-') => { self.zoom(ZOOM_FACTOR); },
+            KeyCode::Char('0') => { self.reset_view(); },
+            KeyCode::Char('v') => { self.show_labels = !self.show_labels; },
+            KeyCode::Char('/') => { self.search = Some(String::new()); },
+            KeyCode::Char('u') => { return vec![Event::UpgradeShip]; },
+            KeyCode::Char('x') => { return vec![Event::SensorSweep]; },
+            // Also here, the star map is gone once the ship is out of the system
+            KeyCode::Char('h') if self.away_team => { return vec![Event::Recall]; },
             KeyCode::Enter => {
                 if let Some(key) = last_key_pressed {
-                    if within_radius(self.selected_pos, self.current_pos, WARP_DISTANCE) && can_warp {
+                    if self.can_warp() {
                         if key == key_event {
                             self.warp_progress = last_press_time.elapsed().as_secs_f64() / Duration::from_secs(WARP_HOLD_DURATION).as_secs_f64();
                             if self.warp_progress > 1.0 {
@@ -75,26 +386,24 @@ impl GalacticMap {
                             // Stop draining fuel after 1 time
                             if !self.warped {
                                 self.warped = true;
-                                self.current_pos = self.selected_pos;
+                                let from = self.current_pos;
+                                let cost = self.warp_cost();
+                                self.current_pos = self.warp_target();
+                                self.selected_pos = self.current_pos;
                                 let mut events = Vec::new();
                                 // Deplete fuel
                                 events.push(Event::Item(ItemDiff{
                                     crystals: 0,
-                                    fuel: -1,
+                                    fuel: -cost,
                                     components: 0,
                                 }));
-
-                                match self.check_for_systems() {
-                                    Some(Some(i)) => {
-                                        self.current_system = Some(i);
-                                        events.push(Event::NewSystem(Some(self.solar_systems[i].clone())));
-                                    },
-                                    Some(None) => {
-                                        self.current_system = None;
-                                        events.push(Event::NewSystem(None));
-                                    },
-                                    None => {}
-                                }
+                                events.extend(self.arrive());
+                                events.push(Event::Log(LogKind::Warp {
+                                    from,
+                                    to: self.current_pos,
+                                    system: self.get_current_system().map(|system| system.name.clone()),
+                                    fuel: cost,
+                                }));
                                 return events;
                             }
                         }
@@ -103,13 +412,53 @@ impl GalacticMap {
             },
             _ => {},
         };
+        self.follow_cursor();
         Vec::new()
     }
 
+    /// Move the ship without using the warp drive
+    pub fn teleport(&mut self, pos: (f64, f64)) -> Vec<Event> {
+        self.current_pos = pos;
+        self.selected_pos = pos;
+        self.follow_cursor();
+        self.arrive()
+    }
+
+    fn arrive(&mut self) -> Vec<Event> {
+        match self.check_for_systems() {
+            Some(Some(i)) => {
+                self.current_system = Some(i);
+                self.discovered.insert(self.solar_systems[i].name.clone());
+                self.follow_route(i);
+                vec![Event::NewSystem(Some(self.solar_systems[i].clone()))]
+            },
+            Some(None) => {
+                self.current_system = None;
+                vec![Event::NewSystem(None)]
+            },
+            None => Vec::new(),
+        }
+    }
+
+    /// Drop the jump once we arrive at it
+    fn follow_route(&mut self, system: usize) {
+        let Some(route) = &mut self.route else { return };
+        if route.jumps.first() == Some(&system) {
+            route.jumps.remove(0);
+        }
+        if route.jumps.is_empty() {
+            self.route = None;
+            self.route_message = Some(format!("Bestemming {} bereikt", self.display_name(&self.solar_systems[system])));
+        }
+    }
+
     pub fn update_system(&mut self) {
         if let Some(system) = self.check_for_systems() {
             self.current_system = system;
         }
+        if let Some(i) = self.current_system {
+            self.discovered.insert(self.solar_systems[i].name.clone());
+        }
     }
 
     pub fn check_for_systems(&self) -> Option<Option<usize>> {
@@ -133,15 +482,48 @@ impl GalacticMap {
     }
 }
 
+impl GalacticMap {
+    fn render_system_list(&self, area: Rect, buf: &mut Buffer, selected: usize) {
+        let [_, popup] = Layout::horizontal([
+            Constraint::Percentage(60),
+            Constraint::Percentage(40),
+        ]).areas(area);
+
+        let items: Vec<Line> = self.systems_by_distance().iter().map(|i| {
+            let system = &self.solar_systems[*i];
+            Line::from(format!("{} ({:.1})", self.display_name(system), distance(self.current_pos, system.pos)))
+        }).collect();
+
+        let block = Block::bordered()
+            .title(" Bestemming ".bold())
+            .title_bottom(Line::from(vec![
+                " Kies ".into(), "<w/s>".green().bold(),
+                " Plan ".into(), "<Enter>".green().bold(),
+                " Sluiten ".into(), "<r> ".green().bold(),
+            ]))
+            .title_alignment(Alignment::Center);
+
+        Clear.render(popup, buf);
+        let mut state = ListState::default().with_selected(Some(selected));
+        StatefulWidget::render(
+            List::new(items).block(block).highlight_style(Style::default().fg(Color::Green).bold()),
+            popup,
+            buf,
+            &mut state,
+        );
+    }
+}
+
 impl Widget for &GalacticMap {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let [pos, main, bar] = Layout::vertical([
+        let [pos, route, main, bar] = Layout::vertical([
+            Constraint::Percentage(3),
             Constraint::Percentage(3),
-            Constraint::Percentage(92),
+            Constraint::Percentage(89),
             Constraint::Percentage(5),
         ]).areas(area);
 
-        let [current, _, selected] = Layout::horizontal([
+        let [current, ship, selected] = Layout::horizontal([
             Constraint::Percentage(25),
             Constraint::Percentage(50),
             Constraint::Percentage(25),
@@ -150,8 +532,13 @@ impl Widget for &GalacticMap {
         Canvas::default()
             .paint(|ctx| {
                 for system in &self.solar_systems {
-                    let mut color = Color::White;
-                    let mut size = MOVE_DISTANCE * 2.0;
+                    if !self.is_visible(system) {
+                        continue;
+                    }
+                    // Unknown blips until they have been visited
+                    let known = self.discovered.contains(&system.name);
+                    let color = if known { Color::White } else { Color::DarkGray };
+                    let size = MOVE_DISTANCE * 2.0 * self.scale();
 
                     // DEBUGGING ONLY
                     // if system.has_component() {
@@ -169,40 +556,123 @@ impl Widget for &GalacticMap {
                         y: system.pos.1,
                         width: size,
                         height: size,
-                        color: color,
+                        color,
                     });
+                    if known && self.show_labels {
+                        ctx.print(system.pos.0, system.pos.1 - MOVE_DISTANCE * 2.0 * self.scale(), Line::from(system.name.clone()).white());
+                    }
+                }
+                // Draw the planned route
+                if let Some(route) = &self.route {
+                    let mut from = self.current_pos;
+                    for jump in &route.jumps {
+                        let to = self.solar_systems[*jump].pos;
+                        ctx.draw(&CanvasLine::new(from.0, from.1, to.0, to.1, Color::Cyan));
+                        from = to;
+                    }
+                }
+                for reading in &self.signals {
+                    ctx.print(reading.pos.0, reading.pos.1 + MOVE_DISTANCE * 2.0 * self.scale(), Line::from(format!("! {}", reading.signal)).fg(reading.signal.color()));
+                }
+                for marker in &self.markers {
+                    ctx.print(marker.pos.0, marker.pos.1, Line::from(format!("x {}", marker.name)).yellow());
                 }
                 // Draw selected position
                 ctx.draw(&Circle{
                     x: self.selected_pos.0,
                     y: self.selected_pos.1,
-                    radius: STAR_DISTANCE,
+                    radius: self.selection_radius(),
                     color: Color::White,
                 });
                 // Draw possible warp radius
                 ctx.draw(&Circle{
                     x: self.current_pos.0,
                     y: self.current_pos.1,
-                    radius: WARP_DISTANCE,
+                    radius: self.ship.warp_range,
                     color: Color::Gray,
                 });
                 // Draw current position
                 ctx.draw(&Circle{
                     x: self.current_pos.0,
                     y: self.current_pos.1,
-                    radius: 0.05,
+                    radius: 0.05 * self.scale(),
                     color: Color::Blue,
                 });
             })
-            .x_bounds([0.0, 30.0])
-            .y_bounds([0.0, 30.0])
+            .x_bounds([self.view_center.0 - self.view_size / 2.0, self.view_center.0 + self.view_size / 2.0])
+            .y_bounds([self.view_center.1 - self.view_size / 2.0, self.view_center.1 + self.view_size / 2.0])
             .render(main, buf);
 
+        let route_line = match &self.route_message {
+            _ if self.search.is_some() => {
+                let text = self.search.clone().unwrap_or_default();
+                let found = match self.search_results(&text).first() {
+                    Some(i) => format!(" -> {}", self.solar_systems[*i].name),
+                    None => String::from(" -> niets gevonden"),
+                };
+                Line::from(vec![
+                    format!("Zoek systeem: {}_", text).into(),
+                    found.cyan(),
+                    " Ga ".into(), "<Enter>".green().bold(),
+                    " Annuleer ".into(), "<Esc>".green().bold(),
+                ])
+            },
+            Some(message) if self.route.is_some() => Line::from(vec![
+                message.clone().cyan(),
+                " Volgende ".into(), "<n>".green().bold(),
+                " Wissen ".into(), "<c>".green().bold(),
+            ]),
+            Some(message) => Line::from(message.clone().yellow()),
+            None => Line::from(vec![
+                " Route kiezen ".into(), "<r>".green().bold(),
+                " Route naar cursor ".into(), "<p>".green().bold(),
+                " Zoom ".into(), "<+/-/0>".green().bold(),
+                " Namen ".into(), "<v>".green().bold(),
+                " Zoek ".into(), "</>".green().bold(),
+                " Volgend systeem ".into(), "<Tab>".green().bold(),
+            ]),
+        };
+        route_line.alignment(Alignment::Center).render(route, buf);
+
+        if let Some(selected) = self.system_list {
+            self.render_system_list(main, buf, selected);
+        }
+
         Line::from(format!("[{:.1}, {:.1}]", self.current_pos.0, self.current_pos.1)).alignment(Alignment::Left).render(current, buf);
-        Line::from(format!("[{:.1}, {:.1}]", self.selected_pos.0, self.selected_pos.1)).alignment(Alignment::Right).render(selected, buf);
+        let selected_line = match self.selected_system() {
+            Some(system) => Line::from(vec![
+                format!("{} ", self.display_name(system)).cyan(),
+                format!("[{:.1}, {:.1}]", self.selected_pos.0, self.selected_pos.1).into(),
+            ]),
+            None => Line::from(format!("[{:.1}, {:.1}]", self.selected_pos.0, self.selected_pos.1)),
+        };
+        selected_line.alignment(Alignment::Right).render(selected, buf);
+        Line::from(vec![
+            format!("Motor {} - bereik {:.1} ", self.ship.engine_level, self.ship.warp_range).into(),
+            " Upgrade ".into(),
+            format!("<u> {} kristallen", self.ship.upgrade_cost()).green().bold(),
+            " Scan ".into(),
+            format!("<x> {} kristallen", Ship::SWEEP_COST).green().bold(),
+        ]).alignment(Alignment::Center).render(ship, buf);
+
+        // Show what the warp costs before committing to it
+        let distance = distance(self.current_pos, self.warp_target());
+        let warp = match self.selected_system() {
+            Some(system) => format!("Warp naar {}", self.display_name(system)),
+            None => String::from("Warp"),
+        };
+        let title = if self.away_team {
+            Line::from(format!("{} - het away team is nog niet terug, terughalen met <h>", warp).red())
+        } else if distance > self.ship.warp_range {
+            Line::from(format!("{} - afstand {:.1}, buiten bereik", warp, distance).red())
+        } else if self.fuel < self.warp_cost() {
+            Line::from(format!("{} - {} brandstof nodig, te weinig brandstof", warp, self.warp_cost()).red())
+        } else {
+            Line::from(format!("{} - afstand {:.1}, kost {} brandstof", warp, distance, self.warp_cost()))
+        };
 
         let line_gauge = Gauge::default()
-            .block(Block::bordered().title("Warp"))
+            .block(Block::bordered().title(title))
             .style(
                 Style::default()
                     .fg(Color::Yellow)
@@ -214,3 +684,55 @@ impl Widget for &GalacticMap {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn system(name: &str, pos: (f64, f64)) -> SolarSystem {
+        SolarSystem { name: String::from(name), pos, planets: Vec::new() }
+    }
+
+    fn press(map: &mut GalacticMap, c: char) {
+        map.handle_press_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), None, std::time::Instant::now());
+    }
+
+    #[test]
+    fn cursor_steps_do_not_skip_systems_in_a_large_galaxy() {
+        let systems = vec![system("Begin", (0.0, 0.0)), system("Doel", (2.3, 0.1)), system("Rand", (300.0, 300.0))];
+        let discovered = HashSet::from([String::from("Begin"), String::from("Doel")]);
+        let mut map = GalacticMap::new(systems, Vec::new(), discovered, (0.0, 0.0));
+        assert!(map.scale() > 2.5, "the view should be zoomed out beyond the size of a star");
+
+        let mut passed = false;
+        while map.selected_pos.0 < 4.0 {
+            press(&mut map, 'd');
+            passed |= map.selected_system().is_some_and(|system| system.name == "Doel");
+        }
+        assert!(passed);
+    }
+
+    #[test]
+    fn away_team_blocks_warping_until_recalled() {
+        let systems = vec![system("Begin", (0.0, 0.0)), system("Doel", (2.0, 0.0))];
+        let mut map = GalacticMap::new(systems, Vec::new(), HashSet::new(), (0.0, 0.0));
+        map.fuel = 10;
+        map.selected_pos = (2.0, 0.0);
+        assert!(map.can_warp());
+
+        map.away_team = true;
+        assert!(!map.can_warp());
+        let events = map.handle_press_event(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE), None, std::time::Instant::now());
+        assert!(matches!(events.as_slice(), [Event::Recall]));
+    }
+
+    #[test]
+    fn warps_land_on_the_selected_system() {
+        let systems = vec![system("Begin", (0.0, 0.0)), system("Doel", (2.0, 0.0)), system("Rand", (300.0, 300.0))];
+        let mut map = GalacticMap::new(systems, Vec::new(), HashSet::new(), (0.0, 0.0));
+        map.selected_pos = (2.0 + map.selection_radius() / 2.0, 0.0);
+        assert_eq!(map.warp_target(), (2.0, 0.0));
+    }
+}
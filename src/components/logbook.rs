@@ -0,0 +1,77 @@
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent}, prelude::*, widgets::{Block, List, ListState, StatefulWidget, Widget}
+};
+
+use crate::{logbook::{LogCategory, LogEntry}, util::format_timestamp};
+
+/// Scrollable view of the logbook, newest entries on top
+#[derive(Default)]
+pub struct Logbook {
+    filter: Option<LogCategory>,
+    scroll: usize,
+}
+
+impl Logbook {
+    fn visible<'a>(&self, entries: &'a [LogEntry]) -> impl Iterator<Item = &'a LogEntry> {
+        let filter = self.filter;
+        entries.iter()
+            .rev()
+            .filter(move |entry| filter.is_none_or(|filter| entry.kind.category() == filter))
+    }
+
+    pub fn handle_press_event(&mut self, key_event: KeyEvent, entries: &[LogEntry]) {
+        match key_event.code {
+            KeyCode::Char('w') => { self.scroll = self.scroll.saturating_sub(1); },
+            KeyCode::Char('s') => {
+                self.scroll = (self.scroll + 1).min(self.visible(entries).count().saturating_sub(1));
+            },
+            KeyCode::Char('f') => {
+                // Cycle through all categories, then back to showing everything
+                self.filter = match self.filter {
+                    None => Some(LogCategory::ALL[0]),
+                    Some(current) => LogCategory::ALL.iter()
+                        .skip_while(|category| **category != current)
+                        .nth(1)
+                        .copied(),
+                };
+                self.scroll = 0;
+            },
+            _ => {},
+        }
+    }
+}
+
+pub struct LogbookView<'a> {
+    pub logbook: &'a Logbook,
+    pub entries: &'a [LogEntry],
+}
+
+impl Widget for LogbookView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let items: Vec<Line> = self.logbook.visible(self.entries)
+            .map(|entry| Line::from(vec![
+                format!("{} ", format_timestamp(entry.timestamp)).dark_gray(),
+                entry.kind.to_string().into(),
+            ]))
+            .collect();
+
+        let filter = match self.logbook.filter {
+            Some(category) => category.to_string(),
+            None => String::from("Alles"),
+        };
+        let block = Block::bordered()
+            .title(format!(" Filter: {} ", filter))
+            .title_bottom(Line::from(vec![
+                " Scrollen ".into(), "<w/s>".green().bold(),
+                " Filter ".into(), "<f> ".green().bold(),
+            ]))
+            .title_alignment(Alignment::Center);
+
+        let mut state = ListState::default()
+            .with_selected(Some(self.logbook.scroll.min(items.len().saturating_sub(1))));
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().fg(Color::Green).bold());
+        StatefulWidget::render(list, area, buf, &mut state);
+    }
+}
@@ -2,3 +2,7 @@ pub mod galaxy_map;
 pub mod star_map;
 pub mod resources;
 pub mod crew;
+pub mod admin;
+pub mod event_popup;
+pub mod logbook;
+pub mod dice;
@@ -1,14 +1,24 @@
 use std::time::Duration;
 
-use ratatui::{crossterm::event::{KeyCode, KeyEvent}, prelude::*, widgets::{canvas::{Canvas}, Block, Gauge}};
+use ratatui::{crossterm::event::{KeyCode, KeyEvent}, prelude::*, widgets::{canvas::{Canvas}, Block, Clear, Gauge, Paragraph, Wrap}};
 
-use crate::{objects::Planet, util::{Event, WARP_HOLD_DURATION}};
+use crate::{crew::{CrewMember, SHIP_LOCATION}, logbook::LogKind, objects::Planet, util::{self, Event, WARP_HOLD_DURATION}};
+
+/// Crew members to pick the away team from, taken when the picker opens
+struct LandingParty {
+    /// Name, where they are, and whether they can go
+    members: Vec<(String, String, bool)>,
+    chosen: Vec<bool>,
+    cursor: usize,
+}
 
 pub struct StarMap {
     pub planets: Vec<Planet>,
     selected_location: usize,
     current_location: usize,
     warp_progress: f64,
+    show_details: bool,
+    landing_party: Option<LandingParty>,
 }
 
 impl StarMap {
@@ -18,10 +28,20 @@ impl StarMap {
             selected_location: 0,
             current_location: 0,
             warp_progress: 0.0,
+            show_details: false,
+            landing_party: None,
         }
     }
 
-    pub fn handle_press_event(&mut self, key_event: KeyEvent, last_key_pressed: Option<KeyEvent>, last_press_time: std::time::Instant, username: String) -> Vec<Event> {
+    /// Whether keys should go to this widget only, instead of the menu as well
+    pub fn captures_input(&self) -> bool {
+        self.landing_party.is_some()
+    }
+
+    pub fn handle_press_event(&mut self, key_event: KeyEvent, last_key_pressed: Option<KeyEvent>, last_press_time: std::time::Instant, username: String, crew: &[CrewMember]) -> Vec<Event> {
+        if let Some(party) = self.landing_party.take() {
+            return self.handle_landing_party(key_event, party, username);
+        }
         match key_event.code {
             KeyCode::Left   => { self.selected_location = (self.selected_location + self.planets.len() - 1) % self.planets.len() },
             KeyCode::Right  => { self.selected_location = (self.selected_location + self.planets.len() + 1) % self.planets.len() },
@@ -41,17 +61,112 @@ impl StarMap {
                     }
                 }
             },
+            KeyCode::Char('i') => { self.show_details = !self.show_details; },
             KeyCode::Char('e') => {
-                // Explore planet
-                let events = self.planets[self.current_location].visit(username);
-                return events;
+                // Pick the away team before exploring
+                let members = crew.iter()
+                    .map(|member| (member.name.clone(), member.location.clone(), member.is_active() && member.location == SHIP_LOCATION))
+                    .collect::<Vec<_>>();
+                self.landing_party = Some(LandingParty {
+                    chosen: vec![false; members.len()],
+                    members,
+                    cursor: 0,
+                });
             },
+            KeyCode::Char('h') => { return vec![Event::Recall]; },
             _ => {},
         }
         Vec::new()
     }
 }
 
+impl StarMap {
+    fn handle_landing_party(&mut self, key_event: KeyEvent, mut party: LandingParty, username: String) -> Vec<Event> {
+        let count = party.members.len().max(1);
+        match key_event.code {
+            KeyCode::Char('w') | KeyCode::Up => { party.cursor = (party.cursor + count - 1) % count; },
+            KeyCode::Char('s') | KeyCode::Down => { party.cursor = (party.cursor + 1) % count; },
+            KeyCode::Char(' ') if party.members.get(party.cursor).is_some_and(|(_, _, available)| *available) => {
+                party.chosen[party.cursor] = !party.chosen[party.cursor];
+            },
+            KeyCode::Enter => {
+                let names: Vec<String> = party.members.iter()
+                    .zip(&party.chosen)
+                    .filter(|(_, chosen)| **chosen)
+                    .map(|((name, _, _), _)| name.clone())
+                    .collect();
+                if !names.is_empty() {
+                    // Land first, so everything found on the planet happens to the away team
+                    let planet = &mut self.planets[self.current_location];
+                    let mut events = vec![
+                        Event::Land { planet: planet.name.clone(), party: names.clone() },
+                        Event::Log(LogKind::Landing { planet: planet.name.clone(), party: names }),
+                    ];
+                    events.extend(planet.visit(username));
+                    return events;
+                }
+            },
+            KeyCode::Esc | KeyCode::Char('e') => { return Vec::new(); },
+            _ => {},
+        }
+        self.landing_party = Some(party);
+        Vec::new()
+    }
+
+    fn render_landing_party(&self, area: Rect, buf: &mut Buffer, party: &LandingParty) {
+        let Some(planet) = self.planets.get(self.current_location) else { return };
+        let mut lines: Vec<Line> = party.members.iter().zip(&party.chosen).enumerate()
+            .map(|(i, ((name, location, available), chosen))| {
+                let check = if *chosen { "[x]" } else { "[ ]" };
+                let line = match available {
+                    true => Line::from(format!("{} {}", check, name)),
+                    false => Line::from(format!("{} {} ({})", check, name, location).dark_gray()),
+                };
+                match i == party.cursor {
+                    true => line.green().bold(),
+                    false => line,
+                }
+            })
+            .collect();
+        if !party.members.iter().any(|(_, _, available)| *available) {
+            lines.push(Line::from("Er is niemand aan boord die kan gaan".red()));
+        }
+
+        let block = Block::bordered()
+            .title(format!(" Away team naar {} ", planet.name).bold())
+            .title_bottom(Line::from(vec![
+                " Kies ".into(), "<w/s>".green().bold(),
+                " Mee ".into(), "<Spatie>".green().bold(),
+                " Landen ".into(), "<Enter>".green().bold(),
+                " Annuleer ".into(), "<Esc> ".green().bold(),
+            ]))
+            .title_alignment(Alignment::Center);
+
+        let area = util::center(area, Constraint::Percentage(50), Constraint::Length(lines.len() as u16 + 2));
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+
+    fn render_details(&self, area: Rect, buf: &mut Buffer) {
+        let Some(planet) = self.planets.get(self.selected_location) else { return };
+        let [_, popup] = Layout::horizontal([
+            Constraint::Percentage(60),
+            Constraint::Percentage(40),
+        ]).areas(area);
+
+        let block = Block::bordered()
+            .title(" Planeet ".bold())
+            .title_bottom(Line::from(vec![" Sluiten ".into(), "<i> ".green().bold()]))
+            .title_alignment(Alignment::Center);
+
+        Clear.render(popup, buf);
+        Paragraph::new(planet.details())
+            .wrap(Wrap { trim: true })
+            .block(block)
+            .render(popup, buf);
+    }
+}
+
 impl Widget for &StarMap {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let [main, bar] = Layout::vertical([
@@ -75,8 +190,19 @@ impl Widget for &StarMap {
             .y_bounds([0.0, 100.0])
             .render(main, buf);
 
+        if self.show_details {
+            self.render_details(main, buf);
+        }
+        if let Some(party) = &self.landing_party {
+            self.render_landing_party(main, buf, party);
+        }
+
         let line_gauge = Gauge::default()
-            .block(Block::bordered().title("Warp"))
+            .block(Block::bordered().title("Warp").title_bottom(Line::from(vec![
+                " Verkennen ".into(), "<e>".green().bold(),
+                " Away team terughalen ".into(), "<h>".green().bold(),
+                " Details ".into(), "<i> ".green().bold(),
+            ])))
             .style(
                 Style::default()
                 .fg(Color::Yellow)
@@ -0,0 +1,566 @@
+use std::fmt;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::logbook::LogKind;
+
+/// Where crew members are when they are not on an away mission
+pub const SHIP_LOCATION: &str = "Schip";
+/// Stats and saves are percentages, rolled under with a d100
+pub const MAX_STAT: u8 = 99;
+pub const MAX_STRESS: u8 = 20;
+
+/// A crew member with a Mothership character sheet
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CrewMember {
+    pub name: String,
+    pub portrait: Portrait,
+    pub role: String,
+    pub location: String,
+
+    pub class: Class,
+    pub stats: Stats,
+    pub saves: Saves,
+    pub health: u8,
+    pub max_health: u8,
+    /// Taken every time health runs out, at max wounds the crew member is out of action
+    pub wounds: u8,
+    pub max_wounds: u8,
+    pub stress: u8,
+    pub skills: Vec<String>,
+    pub loadout: Vec<String>,
+    /// Panic effects and the like, they last until they are cleared
+    pub conditions: Vec<String>,
+}
+
+impl Default for CrewMember {
+    fn default() -> Self {
+        CrewMember {
+            name: String::new(),
+            portrait: Portrait::default(),
+            role: String::new(),
+            location: String::from(SHIP_LOCATION),
+            class: Class::default(),
+            stats: Stats::default(),
+            saves: Saves::default(),
+            health: 15,
+            max_health: 15,
+            wounds: 0,
+            max_wounds: 2,
+            stress: 2,
+            skills: Vec::new(),
+            loadout: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+}
+
+impl CrewMember {
+    pub fn new(name: &str, portrait: Portrait, role: &str, class: Class) -> CrewMember {
+        let mut member = CrewMember {
+            name: String::from(name),
+            portrait,
+            role: String::from(role),
+            class,
+            ..Default::default()
+        };
+        class.apply(&mut member);
+        member
+    }
+
+    /// Target to roll under for a stat or save check
+    pub fn attribute(&self, attribute: Attribute) -> u8 {
+        match attribute {
+            Attribute::Strength => self.stats.strength,
+            Attribute::Speed => self.stats.speed,
+            Attribute::Intellect => self.stats.intellect,
+            Attribute::Combat => self.stats.combat,
+            Attribute::Sanity => self.saves.sanity,
+            Attribute::Fear => self.saves.fear,
+            Attribute::Body => self.saves.body,
+        }
+    }
+
+    pub fn add_stress(&mut self, amount: i8) {
+        self.stress = self.stress.saturating_add_signed(amount).min(MAX_STRESS);
+    }
+
+    /// Roll a d20 against stress, at or under it the crew member panics
+    pub fn panic_check(&mut self, rng: &mut impl Rng) -> PanicCheck {
+        let roll = rng.gen_range(1..=20);
+        let stress = self.stress;
+        let effect = match roll <= stress {
+            true => {
+                let effect = &PANIC_TABLE[roll as usize - 1];
+                self.add_stress(effect.stress);
+                for _ in 0..effect.wounds {
+                    self.damage(self.health);
+                }
+                if !self.conditions.iter().any(|condition| condition == effect.name) {
+                    self.conditions.push(String::from(effect.name));
+                }
+                Some(effect)
+            },
+            false => None,
+        };
+        PanicCheck { roll, stress, effect }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.wounds < self.max_wounds
+    }
+
+    /// Damage comes off health first, every time health runs out it becomes a wound
+    pub fn damage(&mut self, amount: u8) {
+        let mut amount = amount;
+        while amount > 0 && self.is_active() {
+            if amount < self.health {
+                self.health -= amount;
+                return;
+            }
+            amount -= self.health;
+            self.wounds += 1;
+            self.health = self.max_health;
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Stats {
+    pub strength: u8,
+    pub speed: u8,
+    pub intellect: u8,
+    pub combat: u8,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats { strength: 30, speed: 30, intellect: 30, combat: 30 }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Saves {
+    pub sanity: u8,
+    pub fear: u8,
+    pub body: u8,
+}
+
+impl Default for Saves {
+    fn default() -> Self {
+        Saves { sanity: 20, fear: 20, body: 20 }
+    }
+}
+
+pub struct PanicCheck {
+    pub roll: u8,
+    /// Stress at the moment of the check
+    pub stress: u8,
+    /// What happened when the crew member panicked
+    pub effect: Option<&'static PanicEffect>,
+}
+
+impl PanicCheck {
+    pub fn log(&self, name: &str) -> LogKind {
+        LogKind::Panic {
+            name: String::from(name),
+            roll: self.roll,
+            stress: self.stress,
+            effect: self.effect.map(|effect| String::from(effect.name)),
+        }
+    }
+}
+
+pub struct PanicEffect {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub stress: i8,
+    pub wounds: u8,
+}
+
+const fn panic_effect(name: &'static str, description: &'static str, stress: i8, wounds: u8) -> PanicEffect {
+    PanicEffect { name, description, stress, wounds }
+}
+
+/// The Mothership panic table, indexed by the d20 roll of the panic check
+pub const PANIC_TABLE: [PanicEffect; 20] = [
+    panic_effect("Adrenalinekick", "De angst slaat om in focus, [+] op alle worpen.", -2, 0),
+    panic_effect("Zenuwtrek", "Onrustig trillen, de crew eromheen wordt er nerveus van.", 1, 0),
+    panic_effect("Gefluister", "Hoort stemmen die er niet zijn.", 1, 0),
+    panic_effect("Schrikachtig", "[-] op Snelheid.", 0, 0),
+    panic_effect("Overweldigd", "[-] op alle worpen.", 1, 0),
+    panic_effect("Lafheid", "Moet een Angst save halen om te vechten.", 0, 0),
+    panic_effect("Fobie", "Heeft een nieuwe fobie.", 0, 0),
+    panic_effect("Nachtmerries", "Slaapt slecht, rust haalt geen stress weg.", 1, 0),
+    panic_effect("Geen zelfvertrouwen", "[-] op een vaardigheid naar keuze.", 0, 0),
+    panic_effect("Neerslachtig", "[-] op Intellect.", 1, 0),
+    panic_effect("Onheil", "Voelt het einde naderen, [-] op saves.", 1, 0),
+    panic_effect("Achterdochtig", "Vertrouwt de rest van de crew niet meer.", 1, 0),
+    panic_effect("Achtervolgd", "Iets volgt het schip, alleen zij zien het.", 2, 0),
+    panic_effect("Doodsverlangen", "Zoekt het gevaar op.", 0, 0),
+    panic_effect("Profetisch visioen", "Ziet een mogelijke toekomst en kan er niet meer van loskomen.", 1, 0),
+    panic_effect("Catatonisch", "Reageert nergens meer op.", 0, 0),
+    panic_effect("Razernij", "Valt de dichtstbijzijnde dreiging aan.", 0, 0),
+    panic_effect("Spiraal", "Elke mislukte save geeft extra stress.", 2, 0),
+    panic_effect("Opeenstapeling", "[-] op alle worpen, alles gaat mis.", 2, 0),
+    panic_effect("Hartaanval", "Het lichaam geeft op, een wond.", 0, 1),
+];
+
+/// Description of a condition from the panic table
+pub fn condition_description(name: &str) -> Option<&'static str> {
+    PANIC_TABLE.iter().find(|effect| effect.name == name).map(|effect| effect.description)
+}
+
+/// Stats and saves that can be checked
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum Attribute {
+    Strength,
+    Speed,
+    Intellect,
+    Combat,
+    Sanity,
+    Fear,
+    Body,
+}
+
+impl Attribute {
+    pub fn is_save(&self) -> bool {
+        matches!(self, Attribute::Sanity | Attribute::Fear | Attribute::Body)
+    }
+
+    pub const ALL: [Attribute; 7] = [
+        Attribute::Strength, Attribute::Speed, Attribute::Intellect, Attribute::Combat,
+        Attribute::Sanity, Attribute::Fear, Attribute::Body,
+    ];
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            Attribute::Strength => "Kracht",
+            Attribute::Speed => "Snelheid",
+            Attribute::Intellect => "Intellect",
+            Attribute::Combat => "Gevecht",
+            Attribute::Sanity => "Verstand",
+            Attribute::Fear => "Angst",
+            Attribute::Body => "Lichaam",
+        };
+        write!(f, "{}", res)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Class {
+    #[default]
+    Teamster,
+    Marine,
+    Android,
+    Scientist,
+}
+
+impl Class {
+    pub const ALL: [Class; 4] = [Class::Teamster, Class::Marine, Class::Android, Class::Scientist];
+
+    /// Class bonuses and starting gear on top of the base character
+    fn apply(&self, member: &mut CrewMember) {
+        let (stats, saves) = (&mut member.stats, &mut member.saves);
+        let (skills, loadout): (&[&str], &[&str]) = match self {
+            Class::Teamster => {
+                for stat in [&mut stats.strength, &mut stats.speed, &mut stats.intellect, &mut stats.combat] {
+                    *stat += 5;
+                }
+                for save in [&mut saves.sanity, &mut saves.fear, &mut saves.body] {
+                    *save += 10;
+                }
+                (&["Zware machines", "Zero-G"], &["Snijbrander", "Vacuümpak"])
+            },
+            Class::Marine => {
+                stats.combat += 10;
+                saves.body += 10;
+                saves.fear += 20;
+                member.max_wounds += 1;
+                (&["Militaire training", "Atletiek"], &["Pulsgeweer", "Gevechtspantser"])
+            },
+            Class::Android => {
+                stats.intellect += 20;
+                saves.fear += 60;
+                member.max_wounds += 1;
+                (&["Linguïstiek", "Computers"], &["Scanner", "Gereedschapset"])
+            },
+            Class::Scientist => {
+                stats.intellect += 10;
+                saves.sanity += 30;
+                (&["Biologie", "Eerste hulp"], &["Medkit", "Monsterkit"])
+            },
+        };
+        member.skills = skills.iter().map(|skill| String::from(*skill)).collect();
+        member.loadout = loadout.iter().map(|item| String::from(*item)).collect();
+    }
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            Class::Teamster => "Teamster",
+            Class::Marine => "Marinier",
+            Class::Android => "Androïde",
+            Class::Scientist => "Wetenschapper",
+        };
+        write!(f, "{}", res)
+    }
+}
+
+/// Built-in ASCII art, so the save file only has to name it
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Portrait {
+    #[default]
+    Guy,
+    Yoda,
+    Bob,
+    Skeleton,
+    Alien,
+    Xenomorph,
+}
+
+impl Portrait {
+    pub fn art(&self) -> &'static str {
+        match self {
+            Portrait::Guy => GUY,
+            Portrait::Yoda => YODA,
+            Portrait::Bob => BOB,
+            Portrait::Skeleton => SKELETON,
+            Portrait::Alien => ALIEN,
+            Portrait::Xenomorph => XENOMORPH,
+        }
+    }
+}
+
+/// Whether part of the crew is off the ship, it can not warp until they are back
+pub fn has_away_team(crew: &[CrewMember]) -> bool {
+    crew.iter().any(|member| member.location != SHIP_LOCATION)
+}
+
+/// Crew members that event outcomes apply to, the away team on the planet of the event if there is one.
+/// An away team that is down entirely shields the crew on the ship.
+pub fn affected_crew(crew: &[CrewMember], planet: Option<&str>) -> Vec<usize> {
+    let away_team: Vec<usize> = (0..crew.len())
+        .filter(|i| planet.is_some_and(|planet| crew[*i].location == planet))
+        .collect();
+    let affected = match away_team.is_empty() {
+        true => (0..crew.len()).collect(),
+        false => away_team,
+    };
+    affected.into_iter().filter(|i| crew[*i].is_active()).collect()
+}
+
+/// Crew of a new ship
+pub fn default_crew() -> Vec<CrewMember> {
+    vec![
+        CrewMember::new("Yoda", Portrait::Yoda, "Navigator", Class::Scientist),
+        CrewMember::new("Jack Skellington", Portrait::Skeleton, "Monteur", Class::Android),
+        CrewMember::new("???", Portrait::Xenomorph, "Verstekeling", Class::Marine),
+        CrewMember::new("Bob", Portrait::Bob, "Kok", Class::Teamster),
+        CrewMember::new("Yabooiiii", Portrait::Guy, "Piloot", Class::Marine),
+        CrewMember::new("Ally", Portrait::Alien, "Bioloog", Class::Scientist),
+    ]
+}
+
+const YODA: &str = r#"
+                   ____                  
+                _.' :  `._               
+            .-.'`.  ;   .'`.-.           
+   __      / : ___\ ;  /___ ; \      __  
+ ,'_ ""--.:__;".-.";: :".-.":__;.--"" _`,
+ :' `.t""--.. '<@.`;_  ',@>` ..--""j.' `;
+      `:-.._J '-.-'L__ `-- ' L_..-;'     
+        "-.__ ;  .-"  "-.  : __.-"       
+            L ' /.------.\ ' J           
+             "-.   "--"   .-"            
+            __.l"-:_JL_;-";.__           
+         .-j/'.;  ;""""  / .'\"-.        
+       .' /:`. "-.:     .-" .';  `.      
+    .-"  / ;  "-. "-..-" .-"  :    "-.   
+ .+"-.  : :      "-.__.-"      ;-._   \  
+ ; \  `.; ;                    : : "+. ; 
+ :  ;   ; ;                    : ;  : \: 
+"#;
+
+const BOB: &str = r#"
+      .--..--..--..--..--..--.
+    .' \  (`._   (_)     _   \
+  .'    |  '._)         (_)  |
+  \ _.')\      .----..---.   /
+  |(_.'  |    /    .-\-.  \  |
+  \     0|    |   ( O| O) | o|
+   |  _  |  .--.____.'._.-.  |
+   \ (_) | o         -` .-`  |
+    |    \   |`-._ _ _ _ _\ /
+    \    |   |  `. |_||_|   |
+    | o  |    \_      \     |     -.   .-.
+    |.-.  \     `--..-'   O |     `.`-' .'
+  _.'  .' |     `-.-'      /-.__   ' .-'
+.' `-.` '.|='=.='=.='=.='=|._/_ `-'.'
+`-._  `.  |________/\_____|    `-.'
+   .'   ).| '=' '='\/ '=' |
+   `._.`  '---------------'
+           //___\   //___\
+             ||       ||
+             ||_.-.   ||_.-.
+            (_.--__) (_.--__)
+"#;
+
+const SKELETON: &str = r#"
+      .-.
+     (o.o)
+      |=|
+     __|__
+   //.=|=.\\
+  // .=|=. \\
+  \\ .=|=. //
+   \\(_=_)//
+    (:| |:)
+     || ||
+     () ()
+     || ||
+     || ||
+    ==' '==
+"#;
+
+const ALIEN: &str = r#"
+ o            o
+  \          /
+   \        /
+    :-'""'-:
+ .-'  ____  `-.
+( (  (_()_)  ) )
+ `-.   ^^   .-'
+    `._==_.'
+     __)(___
+"#;
+
+const XENOMORPH: &str = r#"
+         __.,,------.._
+      ,'"   _      _   "`.
+     /.__, ._  -=- _"`    Y
+    (.____.-.`      ""`   j
+     VvvvvvV`.Y,.    _.,-'       ,     ,     ,
+        Y    ||,   '"\         ,/    ,/    ./
+        |   ,'  ,     `-..,'_,'/___,'/   ,'/   ,
+   ..  ,;,,',-'"\,'  ,  .     '     ' ""' '--,/    .. ..
+ ,'. `.`---'     `, /  , Y -=-    ,'   ,   ,. .`-..||_|| ..
+ff\\`. `._        /f ,'j j , ,' ,   , f ,  \=\ Y   || ||`||_..
+l` \` `.`."`-..,-' j  /./ /, , / , / /l \   \=\l   || `' || ||...
+ `  `   `-._ `-.,-/ ,' /`"/-/-/-/-"'''"`.`.  `'.\--`'--..`'_`' || ,
+            "`-_,',  ,'  f    ,   /      `._    ``._     ,  `-.`'//         ,
+          ,-"'' _.,-'    l_,-'_,,'          "`-._ . "`. /|     `.'\ ,       |
+        ,',.,-'"          \=) ,`-.         ,    `-'._`.V |       \ // .. . /j
+        |f\\               `._ )-."`.     /|         `.| |        `.`-||-\\/
+        l` \`                 "`._   "`--' j          j' j          `-`---'
+         `  `                     "`,-  ,'/       ,-'"  /
+                                 ,'",__,-'       /,, ,-'
+                                 Vvv'            VVv'
+"#;
+
+const GUY: &str = r#"
+   .------\ /------.
+   |       -       |
+   |               |
+   |               |
+   |               |
+_______________________
+===========.===========
+  / ~~~~~     ~~~~~ \
+ /|     |     |\
+ W   ---  / \  ---   W
+ \.      |o o|      ./
+  |                 |
+  \    #########    /
+   \  ## ----- ##  /
+    \##         ##/
+     \_____v_____/
+"#;
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn panic_applies_the_table_effect_once() {
+        for seed in 0..20 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let mut member = CrewMember { stress: MAX_STRESS, ..Default::default() };
+            let check = member.panic_check(&mut rng);
+            let effect = check.effect.expect("every roll to be at or under the maximum stress");
+
+            assert_eq!(effect.name, PANIC_TABLE[check.roll as usize - 1].name);
+            assert_eq!(member.stress, MAX_STRESS.saturating_add_signed(effect.stress).min(MAX_STRESS));
+            assert_eq!(member.wounds, effect.wounds.min(member.max_wounds));
+            assert_eq!(member.conditions, vec![String::from(effect.name)]);
+        }
+    }
+
+    #[test]
+    fn no_panic_above_the_stress() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut member = CrewMember { stress: 0, ..Default::default() };
+        let before = member.clone();
+        assert!(member.panic_check(&mut rng).effect.is_none());
+        assert_eq!(member, before);
+    }
+
+    #[test]
+    fn damage_rolls_over_into_wounds() {
+        let mut member = CrewMember { health: 10, max_health: 10, max_wounds: 3, ..Default::default() };
+        member.damage(4);
+        assert_eq!((member.health, member.wounds), (6, 0));
+        member.damage(6);
+        assert_eq!((member.health, member.wounds), (10, 1));
+        member.damage(13);
+        assert_eq!((member.health, member.wounds), (7, 2));
+        assert!(member.is_active());
+    }
+
+    fn crew_at(locations: &[&str]) -> Vec<CrewMember> {
+        locations.iter().map(|location| CrewMember { location: String::from(*location), ..Default::default() }).collect()
+    }
+
+    #[test]
+    fn events_hit_the_away_team() {
+        let crew = crew_at(&[SHIP_LOCATION, "Korvax-7 I", "Korvax-7 I", "Korvax-7 II"]);
+        assert!(has_away_team(&crew));
+        assert_eq!(affected_crew(&crew, Some("Korvax-7 I")), vec![1, 2]);
+    }
+
+    #[test]
+    fn events_without_an_away_team_hit_the_whole_crew() {
+        let mut crew = crew_at(&[SHIP_LOCATION, SHIP_LOCATION, SHIP_LOCATION]);
+        crew[1].wounds = crew[1].max_wounds;
+        assert!(!has_away_team(&crew));
+        assert_eq!(affected_crew(&crew, Some("Korvax-7 I")), vec![0, 2]);
+        assert_eq!(affected_crew(&crew, None), vec![0, 2]);
+    }
+
+    #[test]
+    fn a_fallen_away_team_shields_the_ship() {
+        let mut crew = crew_at(&[SHIP_LOCATION, "Korvax-7 I"]);
+        crew[1].wounds = crew[1].max_wounds;
+        assert!(affected_crew(&crew, Some("Korvax-7 I")).is_empty());
+    }
+
+    #[test]
+    fn damage_stops_at_max_wounds() {
+        let mut member = CrewMember { health: 10, max_health: 10, max_wounds: 2, ..Default::default() };
+        member.damage(u8::MAX);
+        assert_eq!(member.wounds, 2);
+        assert!(!member.is_active());
+        member.damage(5);
+        assert_eq!(member.wounds, 2);
+    }
+}
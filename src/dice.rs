@@ -0,0 +1,237 @@
+use std::{fmt, str::FromStr};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Advantage [+] rolls twice and keeps the best, disadvantage [-] keeps the worst
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum RollMode {
+    #[default]
+    Normal,
+    Advantage,
+    Disadvantage,
+}
+
+impl RollMode {
+    pub fn next(&self) -> RollMode {
+        match self {
+            RollMode::Normal => RollMode::Advantage,
+            RollMode::Advantage => RollMode::Disadvantage,
+            RollMode::Disadvantage => RollMode::Normal,
+        }
+    }
+}
+
+impl fmt::Display for RollMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            RollMode::Normal => "",
+            RollMode::Advantage => " [+]",
+            RollMode::Disadvantage => " [-]",
+        };
+        write!(f, "{}", res)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum CheckOutcome {
+    CriticalFailure,
+    Failure,
+    Success,
+    CriticalSuccess,
+}
+
+impl fmt::Display for CheckOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            CheckOutcome::CriticalFailure => "kritieke mislukking",
+            CheckOutcome::Failure => "mislukt",
+            CheckOutcome::Success => "gelukt",
+            CheckOutcome::CriticalSuccess => "kritiek succes",
+        };
+        write!(f, "{}", res)
+    }
+}
+
+/// A d100 stat or save check, rolled under the target
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Check {
+    pub target: u8,
+    pub mode: RollMode,
+    /// Every d100 that was rolled, 0 to 99
+    pub rolls: Vec<u8>,
+    /// The roll that counts
+    pub roll: u8,
+    pub outcome: CheckOutcome,
+}
+
+impl Check {
+    pub fn roll(rng: &mut impl Rng, target: u8, mode: RollMode) -> Check {
+        let count = if mode == RollMode::Normal { 1 } else { 2 };
+        let rolls: Vec<u8> = (0..count).map(|_| rng.gen_range(0..100)).collect();
+        let results = rolls.iter().map(|roll| (*roll, check_outcome(*roll, target)));
+        // Best result first, a lower roll is better when the outcome is the same
+        let rank = |(roll, outcome): &(u8, CheckOutcome)| (*outcome, std::cmp::Reverse(*roll));
+        let (roll, outcome) = match mode {
+            RollMode::Disadvantage => results.min_by(|a, b| rank(a).partial_cmp(&rank(b)).expect("outcomes to be ordered")),
+            _ => results.max_by(|a, b| rank(a).partial_cmp(&rank(b)).expect("outcomes to be ordered")),
+        }.expect("at least one roll");
+        Check { target, mode, rolls, roll, outcome }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.outcome >= CheckOutcome::Success
+    }
+}
+
+/// Doubles are criticals, the roll has to be under the target to succeed
+fn check_outcome(roll: u8, target: u8) -> CheckOutcome {
+    let success = roll < target;
+    let double = roll / 10 == roll % 10;
+    match (success, double) {
+        (true, true) => CheckOutcome::CriticalSuccess,
+        (true, false) => CheckOutcome::Success,
+        (false, true) => CheckOutcome::CriticalFailure,
+        (false, false) => CheckOutcome::Failure,
+    }
+}
+
+/// Dice expression like "2d10+3"
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Dice {
+    pub count: u8,
+    pub sides: u8,
+    pub modifier: i32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseDiceError(String);
+
+impl fmt::Display for ParseDiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\" is geen worp, gebruik bijvoorbeeld 2d10+3", self.0)
+    }
+}
+
+impl FromStr for Dice {
+    type Err = ParseDiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDiceError(String::from(s));
+        let text: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+        let (count, rest) = text.split_once('d').ok_or_else(error)?;
+        let (sides, modifier) = match rest.find(['+', '-']) {
+            Some(i) => (&rest[..i], rest[i..].parse::<i32>().map_err(|_| error())?),
+            None => (rest, 0),
+        };
+        let count = match count {
+            "" => 1,
+            count => count.parse::<u8>().map_err(|_| error())?,
+        };
+        let sides = sides.parse::<u8>().map_err(|_| error())?;
+        if count == 0 || sides == 0 {
+            return Err(error());
+        }
+        Ok(Dice { count, sides, modifier })
+    }
+}
+
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)?;
+        match self.modifier {
+            0 => Ok(()),
+            m if m > 0 => write!(f, "+{}", m),
+            m => write!(f, "{}", m),
+        }
+    }
+}
+
+impl Dice {
+    pub fn roll(&self, rng: &mut impl Rng) -> DiceRoll {
+        let rolls: Vec<u8> = (0..self.count).map(|_| rng.gen_range(1..=self.sides)).collect();
+        let total = (rolls.iter().map(|roll| *roll as i32).sum::<i32>() + self.modifier).max(0);
+        DiceRoll { dice: *self, rolls, total }
+    }
+}
+
+/// Result of rolling a dice expression, for damage and the like
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct DiceRoll {
+    pub dice: Dice,
+    pub rolls: Vec<u8>,
+    pub total: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn parses_dice_expressions() {
+        assert_eq!("2d10+3".parse(), Ok(Dice { count: 2, sides: 10, modifier: 3 }));
+        assert_eq!("d20 - 1".parse(), Ok(Dice { count: 1, sides: 20, modifier: -1 }));
+        assert_eq!("3D6".parse(), Ok(Dice { count: 3, sides: 6, modifier: 0 }));
+    }
+
+    #[test]
+    fn refuses_invalid_expressions() {
+        for text in ["", "2x10", "0d10", "2d0", "2d", "2d10+", "2d10+x"] {
+            assert!(text.parse::<Dice>().is_err(), "{} should not parse", text);
+        }
+    }
+
+    #[test]
+    fn display_roundtrips() {
+        for text in ["2d10+3", "1d20-1", "3d6"] {
+            assert_eq!(text.parse::<Dice>().map(|dice| dice.to_string()), Ok(String::from(text)));
+        }
+    }
+
+    #[test]
+    fn rolls_stay_within_the_dice() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let dice = Dice { count: 2, sides: 10, modifier: 3 };
+        for _ in 0..100 {
+            let roll = dice.roll(&mut rng);
+            assert!((5..=23).contains(&roll.total));
+            assert_eq!(roll.total, roll.rolls.iter().map(|roll| *roll as i32).sum::<i32>() + 3);
+        }
+    }
+
+    #[test]
+    fn doubles_are_criticals() {
+        assert_eq!(check_outcome(11, 40), CheckOutcome::CriticalSuccess);
+        assert_eq!(check_outcome(39, 40), CheckOutcome::Success);
+        assert_eq!(check_outcome(40, 40), CheckOutcome::Failure);
+        assert_eq!(check_outcome(55, 40), CheckOutcome::CriticalFailure);
+        assert_eq!(check_outcome(0, 40), CheckOutcome::CriticalSuccess);
+    }
+
+    #[test]
+    fn advantage_keeps_the_best_roll() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        for _ in 0..100 {
+            let check = Check::roll(&mut rng, 50, RollMode::Advantage);
+            assert_eq!(check.rolls.len(), 2);
+            let best = check.rolls.iter().map(|roll| check_outcome(*roll, 50))
+                .fold(CheckOutcome::CriticalFailure, |a, b| if b > a { b } else { a });
+            assert_eq!(check.outcome, best);
+        }
+    }
+
+    #[test]
+    fn disadvantage_keeps_the_worst_roll() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for _ in 0..100 {
+            let check = Check::roll(&mut rng, 50, RollMode::Disadvantage);
+            assert_eq!(check.rolls.len(), 2);
+            let worst = check.rolls.iter().map(|roll| check_outcome(*roll, 50))
+                .fold(CheckOutcome::CriticalSuccess, |a, b| if b < a { b } else { a });
+            assert_eq!(check.outcome, worst);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::io;
+use std::time::Instant;
+
+use ratatui::{
+    crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind}, prelude::*, widgets::{Block, Paragraph, Widget, Wrap}
+};
+use tachyonfx::{fx, EffectManager};
+
+use crate::{storage::StorageError, tui, user::DEFAULT_GAME_MASTER, util};
+
+pub enum ErrorChoice {
+    Generate,
+    Open(String),
+    Quit,
+}
+
+/// Shown when the save file could not be loaded, instead of crashing
+pub struct ErrorScreen {
+    error: StorageError,
+    choice: Option<ErrorChoice>,
+    path: String,
+    entering_path: bool,
+    effects: EffectManager<()>,
+}
+
+impl ErrorScreen {
+    pub fn new(error: StorageError) -> ErrorScreen {
+        let mut effects: EffectManager<()> = EffectManager::default();
+        effects.add_effect(
+            fx::prolong_start(0, fx::coalesce(1000))
+        );
+        ErrorScreen {
+            path: error.path().to_string(),
+            error,
+            choice: None,
+            entering_path: false,
+            effects,
+        }
+    }
+
+    pub fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<ErrorChoice> {
+        let mut last_frame = Instant::now();
+
+        while self.choice.is_none() {
+            let elapsed = last_frame.elapsed();
+            last_frame = Instant::now();
+
+            terminal.draw(|frame| {
+                let area = frame.area();
+                frame.render_widget(&mut *self, area);
+                self.effects.process_effects(elapsed.into(), frame.buffer_mut(), area);
+            })?;
+            self.handle_events()?;
+        }
+        Ok(self.choice.take().expect("choice to be made"))
+    }
+
+    fn handle_events(&mut self) -> io::Result<()> {
+        if event::poll(std::time::Duration::from_millis(50))? {
+            if let event::Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_press_event(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_press_event(&mut self, key_event: KeyEvent) {
+        if self.entering_path {
+            match key_event.code {
+                KeyCode::Esc => { self.entering_path = false; },
+                KeyCode::Enter => { self.choice = Some(ErrorChoice::Open(self.path.clone())); },
+                KeyCode::Char(char) => { self.path.push(char); },
+                KeyCode::Backspace => { self.path.pop(); },
+                _ => {},
+            }
+            return;
+        }
+        match key_event.code {
+            KeyCode::Char('n') => { self.choice = Some(ErrorChoice::Generate); },
+            KeyCode::Char('o') => { self.entering_path = true; },
+            KeyCode::Char('q') | KeyCode::Esc => { self.choice = Some(ErrorChoice::Quit); },
+            _ => {},
+        }
+    }
+}
+
+impl Widget for &mut ErrorScreen {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut text = Text::from(util::TITLE_HEADER)
+            .fg(Color::Green);
+
+        let mut lines: Vec<_> = vec![
+            Line::from(""),
+            Line::from("FOUT".red().bold()),
+            Line::from(self.error.to_string().white()),
+            Line::from(""),
+        ];
+        if self.entering_path {
+            lines.extend([
+                Line::from("BESTAND".red()),
+                Line::from(self.path.clone().white()),
+                Line::from(""),
+                Line::from(vec![" Openen ".into(), "<Enter>".green().bold(), " Terug ".into(), "<Esc>".green().bold()]),
+            ]);
+        } else {
+            lines.extend([
+                Line::from(vec![
+                    "<n>".green().bold(),
+                    format!(" Nieuw sterrenstelsel genereren, inloggen als {0} / {0}", DEFAULT_GAME_MASTER).into(),
+                ]),
+                Line::from(vec!["<o>".green().bold(), " Ander bestand openen".into()]),
+                Line::from(vec!["<q>".green().bold(), " Afsluiten".into()]),
+            ]);
+        }
+        text.extend(Text::from(lines));
+
+        let area = util::center(
+            area,
+            Constraint::Length((text.width()+10).min(area.width as usize) as u16),
+            Constraint::Length((text.height()+5) as u16),
+        );
+
+        let block = Block::bordered()
+            .border_type(ratatui::widgets::BorderType::Double);
+
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(block)
+            .centered()
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A random event as written by the game master, stored in the save file
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct EventDefinition {
+    pub title: String,
+    pub text: String,
+    pub choices: Vec<Choice>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct Choice {
+    pub label: String,
+    /// Flavor text shown after picking this choice
+    pub result: String,
+    pub outcomes: Vec<Outcome>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum Outcome {
+    Resources { crystals: i32, fuel: i32, reputation: i32 },
+    /// Damage to one random member of the away team on the planet of the event, or of the whole crew
+    CrewDamage { amount: u8 },
+    /// Raise the stress of the away team on the planet of the event, or of the whole crew
+    Stress { amount: u8 },
+    /// The away team on the planet of the event, or the whole crew, rolls on the panic table
+    PanicCheck,
+    /// Move the ship, to a random place when no position is given
+    Teleport { pos: Option<(f64, f64)> },
+    /// Put a marker on the map, at the current position when no position is given
+    Marker { name: String, pos: Option<(f64, f64)> },
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Resources { crystals, fuel, reputation } => {
+                let parts: Vec<String> = [(crystals, "kristallen"), (fuel, "brandstof"), (reputation, "reputatie")]
+                    .iter()
+                    .filter(|(amount, _)| **amount != 0)
+                    .map(|(amount, name)| format!("{:+} {}", amount, name))
+                    .collect();
+                write!(f, "{}", parts.join(", "))
+            },
+            Outcome::CrewDamage { amount } => write!(f, "{} schade aan de crew", amount),
+            Outcome::Stress { amount } => write!(f, "+{} stress voor de crew", amount),
+            Outcome::PanicCheck => write!(f, "De crew moet een paniekcheck doen"),
+            Outcome::Teleport { .. } => write!(f, "Het schip is verplaatst"),
+            Outcome::Marker { name, .. } => write!(f, "{} gemarkeerd op de kaart", name),
+        }
+    }
+}
+
+/// A triggered random event, waiting for the game master
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct QueuedEvent {
+    pub user: String,
+    pub planet: String,
+    pub system: String,
+    pub title: String,
+    /// Unix timestamp of when the event was triggered
+    pub timestamp: i64,
+    pub status: EventStatus,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum EventStatus {
+    #[default]
+    Pending,
+    Acknowledged,
+    Resolved,
+}
+
+impl fmt::Display for EventStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            EventStatus::Pending => "Nieuw",
+            EventStatus::Acknowledged => "Gezien",
+            EventStatus::Resolved => "Afgehandeld",
+        };
+        write!(f, "{}", res)
+    }
+}
+
+/// A named point on the galactic map, placed by an event
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct Marker {
+    pub name: String,
+    pub pos: (f64, f64),
+}
+
+fn choice(label: &str, result: &str, outcomes: Vec<Outcome>) -> Choice {
+    Choice {
+        label: label.to_string(),
+        result: result.to_string(),
+        outcomes,
+    }
+}
+
+fn resources(crystals: i32, fuel: i32, reputation: i32) -> Outcome {
+    Outcome::Resources { crystals, fuel, reputation }
+}
+
+/// Events for a new save, the game master can change them in the save file
+pub fn default_events() -> Vec<EventDefinition> {
+    vec![
+        EventDefinition {
+            title: String::from("Noodsignaal"),
+            text: String::from("Een zwak noodsignaal komt uit een verlaten vrachtschip. De romp is koud en de sluis staat open."),
+            choices: vec![
+                choice("Aan boord gaan", "In het ruim liggen kristallen, maar iets in het donker verweert zich.", vec![
+                    resources(10, 0, 0),
+                    Outcome::CrewDamage { amount: 1 },
+                    Outcome::Stress { amount: 1 },
+                ]),
+                choice("Het signaal doorgeven", "De leiding waardeert de melding.", vec![resources(0, 0, 2)]),
+                choice("Negeren", "Het signaal sterft langzaam weg.", vec![]),
+            ],
+        },
+        EventDefinition {
+            title: String::from("Wormgat"),
+            text: String::from("De sensoren slaan op hol. Vlak voor het schip draait een wormgat."),
+            choices: vec![
+                choice("Erin vliegen", "Sterren rekken uit tot strepen, daarna is alles anders.", vec![
+                    Outcome::Teleport { pos: None },
+                ]),
+                choice("Markeren en wegvliegen", "De coordinaten worden opgeslagen voor later.", vec![
+                    Outcome::Marker { name: String::from("Wormgat"), pos: None },
+                ]),
+            ],
+        },
+        EventDefinition {
+            title: String::from("Smokkelaars"),
+            text: String::from("Een schip zonder registratie vraagt om te handelen. De wapens staan op jullie gericht."),
+            choices: vec![
+                choice("Handelen", "Kristallen voor brandstof, geen vragen.", vec![resources(-5, 3, 0)]),
+                choice("Aanvallen", "Na een kort vuurgevecht is hun lading van jullie.", vec![
+                    resources(15, 0, -1),
+                    Outcome::CrewDamage { amount: 2 },
+                ]),
+                choice("Wegvliegen", "Ze laten jullie gaan.", vec![]),
+            ],
+        },
+        EventDefinition {
+            title: String::from("Buitenaardse ruine"),
+            text: String::from("Onder het oppervlak liggen gangen die niet door mensen zijn gebouwd."),
+            choices: vec![
+                choice("Onderzoeken", "De muren fluisteren. Niet iedereen komt ongeschonden terug.", vec![
+                    resources(0, 0, 3),
+                    Outcome::CrewDamage { amount: 1 },
+                    Outcome::Stress { amount: 2 },
+                    Outcome::PanicCheck,
+                ]),
+                choice("Coordinaten noteren", "Misschien later, met betere uitrusting.", vec![
+                    Outcome::Marker { name: String::from("Ruine"), pos: None },
+                ]),
+            ],
+        },
+    ]
+}
@@ -0,0 +1,231 @@
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    objects::{Atmosphere, Biosphere, Environment, Planet, PlanetSize, PlanetType, SolarSystem},
+    util::{distance, MAP_SIZE},
+};
+
+const NAME_START: [&str; 20] = [
+    "Al", "Bel", "Cor", "Del", "Eri", "Fal", "Gan", "Hel", "Ix", "Jor",
+    "Kal", "Lum", "Mor", "Nex", "Or", "Pra", "Ry", "Sol", "Tau", "Vex",
+];
+const NAME_END: [&str; 12] = [
+    "ara", "ion", "us", "ea", "on", "ix", "or", "is", "ax", "une", "eth", "oria",
+];
+const NUMERALS: [&str; 8] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII"];
+const POINTS_OF_INTEREST: [&str; 12] = [
+    "Verlaten kolonie",
+    "Neergestort schip",
+    "Buitenaardse ruines",
+    "Mijnbouwpost",
+    "Onderzoeksstation",
+    "IJsgrotten",
+    "Actieve vulkanen",
+    "Noodbaken",
+    "Kristalvelden",
+    "Wrakstukken in een baan",
+    "Ondergrondse tunnels",
+    "Vreemde monolieten",
+];
+
+/// Margin between the edge of the map and the outermost solar systems
+const MAP_MARGIN: f64 = 1.0;
+/// Minimum distance between two solar systems, so they never overlap on the map
+const MIN_SYSTEM_DISTANCE: f64 = 1.0;
+
+/// Parameters for generating a galaxy, the same config always gives the same galaxy
+pub struct GalaxyConfig {
+    pub seed: u64,
+    pub systems: usize,
+    pub components: usize,
+    /// Chance for each planet to hold a random event
+    pub event_density: f64,
+    pub planets_per_system: (usize, usize),
+    pub max_crystals: i32,
+    pub max_fuel: i32,
+}
+
+impl GalaxyConfig {
+    pub fn new(seed: u64) -> Self {
+        GalaxyConfig {
+            seed,
+            systems: 40,
+            components: 10,
+            event_density: 0.15,
+            planets_per_system: (2, 6),
+            max_crystals: 10,
+            max_fuel: 3,
+        }
+    }
+}
+
+pub fn generate_galaxy(config: &GalaxyConfig) -> Vec<SolarSystem> {
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+
+    let mut systems: Vec<SolarSystem> = Vec::new();
+    let mut attempts = 0;
+    while systems.len() < config.systems && attempts < config.systems * 100 {
+        attempts += 1;
+        let pos = (
+            rng.gen_range(MAP_MARGIN..MAP_SIZE - MAP_MARGIN),
+            rng.gen_range(MAP_MARGIN..MAP_SIZE - MAP_MARGIN),
+        );
+        if systems.iter().any(|system| distance(system.pos, pos) < MIN_SYSTEM_DISTANCE) {
+            continue;
+        }
+
+        let name = generate_name(&mut rng, &systems);
+        let planets = generate_planets(&mut rng, config, &name);
+        systems.push(SolarSystem { name, pos, planets });
+    }
+
+    // Every planet has the same chance to hold an event
+    for system in systems.iter_mut() {
+        for planet in system.planets.iter_mut() {
+            planet.has_event = rng.gen_bool(config.event_density.clamp(0.0, 1.0));
+        }
+    }
+
+    // Components are spread over unique planets, so the total is exact
+    let mut planets: Vec<(usize, usize)> = systems.iter()
+        .enumerate()
+        .flat_map(|(i, system)| (0..system.planets.len()).map(move |j| (i, j)))
+        .collect();
+    planets.shuffle(&mut rng);
+    for (i, j) in planets.into_iter().take(config.components) {
+        systems[i].planets[j].has_component = true;
+    }
+
+    systems
+}
+
+fn generate_name(rng: &mut ChaCha8Rng, existing: &[SolarSystem]) -> String {
+    loop {
+        let name = format!(
+            "{}{}-{}",
+            NAME_START.choose(rng).unwrap(),
+            NAME_END.choose(rng).unwrap(),
+            rng.gen_range(1..100),
+        );
+        if !existing.iter().any(|system| system.name == name) {
+            return name;
+        }
+    }
+}
+
+fn generate_planets(rng: &mut ChaCha8Rng, config: &GalaxyConfig, system_name: &str) -> Vec<Planet> {
+    let (min, max) = config.planets_per_system;
+    let count = rng.gen_range(min..=max.max(min)).min(NUMERALS.len());
+
+    // Spread the planets evenly over the width of the star map
+    let spacing = 100.0 / (count + 1) as f64;
+    (0..count).map(|i| {
+        let planet_type = roll_planet_type(rng);
+        let environment = roll_environment(rng, &planet_type);
+        Planet::new(
+            format!("{} {}", system_name, NUMERALS[i]),
+            (spacing * (i + 1) as f64, rng.gen_range(30.0..70.0)),
+            planet_type,
+            environment,
+            rng.gen_range(0..=config.max_crystals),
+            rng.gen_range(0..=config.max_fuel),
+        )
+    }).collect()
+}
+
+// Planet tables, loosely based on Matt Umland's rimspace planet generator
+
+fn roll_planet_type(rng: &mut ChaCha8Rng) -> PlanetType {
+    match rng.gen_range(1..=10) {
+        1..=3 => PlanetType::Gas,
+        4..=8 => PlanetType::Terrestrial,
+        _ => PlanetType::Ocean,
+    }
+}
+
+fn roll_environment(rng: &mut ChaCha8Rng, planet_type: &PlanetType) -> Environment {
+    let is_gas = matches!(planet_type, PlanetType::Gas);
+
+    // Gas giants are never small
+    let size = match rng.gen_range(1..=10) + if is_gas { 5 } else { 0 } {
+        1..=3 => PlanetSize::Small,
+        4..=7 => PlanetSize::Medium,
+        8..=9 => PlanetSize::Large,
+        _ => PlanetSize::Huge,
+    };
+    let atmosphere = match rng.gen_range(1..=10) {
+        _ if is_gas => Atmosphere::Dense,
+        1..=2 => Atmosphere::None,
+        3..=4 => Atmosphere::Thin,
+        5..=6 => Atmosphere::Breathable,
+        7 => Atmosphere::Dense,
+        8..=9 => Atmosphere::Toxic,
+        _ => Atmosphere::Corrosive,
+    };
+    let temp = match rng.gen_range(1..=10) {
+        1 => rng.gen_range(-200..-100),
+        2..=3 => rng.gen_range(-100..-20),
+        4..=7 => rng.gen_range(-20..40),
+        8..=9 => rng.gen_range(40..150),
+        _ => rng.gen_range(150..450),
+    };
+    let gravity: f32 = match size {
+        PlanetSize::Small => rng.gen_range(0.1..0.6),
+        PlanetSize::Medium => rng.gen_range(0.6..1.3),
+        PlanetSize::Large => rng.gen_range(1.3..2.2),
+        PlanetSize::Huge => rng.gen_range(2.2..4.0),
+    };
+    let hours_per_day = match rng.gen_range(1..=10) {
+        1 => 0,
+        2..=8 => rng.gen_range(8..48),
+        _ => rng.gen_range(48..500),
+    };
+
+    // Life only shows up in somewhat hospitable places
+    let habitable = (-60..80).contains(&temp)
+        && matches!(atmosphere, Atmosphere::Thin | Atmosphere::Breathable | Atmosphere::Dense);
+    let biosphere = match (habitable, rng.gen_range(1..=10)) {
+        (false, 10) => Biosphere::Microbial,
+        (false, _) => Biosphere::None,
+        (true, 1..=3) => Biosphere::None,
+        (true, 4..=5) => Biosphere::Microbial,
+        (true, 6..=8) => Biosphere::Flora,
+        (true, _) => Biosphere::Fauna,
+    };
+
+    let poi_count = rng.gen_range(0..=2);
+    let points_of_interest = POINTS_OF_INTEREST
+        .choose_multiple(rng, poi_count)
+        .map(|poi| poi.to_string())
+        .collect();
+
+    Environment {
+        size,
+        atmosphere,
+        temp,
+        gravity,
+        hours_per_day,
+        biosphere,
+        points_of_interest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_json(seed: u64) -> serde_json::Value {
+        serde_json::to_value(generate_galaxy(&GalaxyConfig::new(seed))).expect("galaxy to serialize")
+    }
+
+    #[test]
+    fn same_seed_gives_same_galaxy() {
+        assert_eq!(generate_json(42), generate_json(42));
+    }
+
+    #[test]
+    fn different_seeds_give_different_galaxies() {
+        assert_ne!(generate_json(1), generate_json(2));
+    }
+}
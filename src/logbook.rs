@@ -0,0 +1,140 @@
+use std::fmt;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{crew::Attribute, dice::{Check, DiceRoll}, objects::SignalReading};
+
+/// One line in the ship logbook, entries are only ever appended
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct LogEntry {
+    /// Unix timestamp of when it happened
+    pub timestamp: i64,
+    pub kind: LogKind,
+}
+
+impl LogEntry {
+    pub fn new(kind: LogKind) -> Self {
+        LogEntry {
+            timestamp: Utc::now().timestamp(),
+            kind,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum LogKind {
+    Warp { from: (f64, f64), to: (f64, f64), system: Option<String>, fuel: i32 },
+    Visit { planet: String, crystals: i32, fuel: i32, components: i32 },
+    RandomEvent { planet: String, title: String },
+    EventChoice { title: String, choice: String },
+    Sweep { pos: (f64, f64), readings: Vec<SignalReading> },
+    Injury { name: String, amount: u8 },
+    Landing { planet: String, party: Vec<String> },
+    Recall { party: Vec<String> },
+    Check { name: String, attribute: Attribute, check: Check },
+    Roll { name: String, roll: DiceRoll },
+    Panic { name: String, roll: u8, stress: u8, effect: Option<String> },
+}
+
+impl Default for LogKind {
+    fn default() -> Self {
+        LogKind::EventChoice { title: String::new(), choice: String::new() }
+    }
+}
+
+impl LogKind {
+    pub fn category(&self) -> LogCategory {
+        match self {
+            LogKind::Warp { .. } => LogCategory::Warp,
+            LogKind::Visit { .. } | LogKind::Landing { .. } | LogKind::Recall { .. } => LogCategory::Visit,
+            LogKind::RandomEvent { .. } | LogKind::EventChoice { .. } | LogKind::Injury { .. } => LogCategory::Event,
+            LogKind::Sweep { .. } => LogCategory::Scan,
+            LogKind::Check { .. } | LogKind::Roll { .. } | LogKind::Panic { .. } => LogCategory::Dice,
+        }
+    }
+}
+
+impl fmt::Display for LogKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogKind::Warp { from, to, system, fuel } => {
+                write!(f, "Warp van [{:.1}, {:.1}] naar [{:.1}, {:.1}]", from.0, from.1, to.0, to.1)?;
+                if let Some(system) = system {
+                    write!(f, " ({})", system)?;
+                }
+                write!(f, ", {} brandstof verbruikt", fuel)
+            },
+            LogKind::Visit { planet, crystals, fuel, components } => {
+                write!(f, "{} verkend: {} kristallen, {} brandstof", planet, crystals, fuel)?;
+                if *components > 0 {
+                    write!(f, ", {} componenten", components)?;
+                }
+                Ok(())
+            },
+            LogKind::RandomEvent { planet, title } => write!(f, "Event op {}: {}", planet, title),
+            LogKind::EventChoice { title, choice } => write!(f, "{}: gekozen voor \"{}\"", title, choice),
+            LogKind::Landing { planet, party } => write!(f, "Away team naar {}: {}", planet, party.join(", ")),
+            LogKind::Recall { party } => write!(f, "Away team terug aan boord: {}", party.join(", ")),
+            LogKind::Injury { name, amount } => write!(f, "{} is gewond geraakt: {} schade", name, amount),
+            LogKind::Check { name, attribute, check } => {
+                write!(f, "{} rolt {}{}: {:02} onder {}", name, attribute, check.mode, check.roll, check.target)?;
+                if check.rolls.len() > 1 {
+                    let rolls: Vec<String> = check.rolls.iter().map(|roll| format!("{:02}", roll)).collect();
+                    write!(f, " ({})", rolls.join(", "))?;
+                }
+                write!(f, ", {}", check.outcome)
+            },
+            LogKind::Roll { name, roll } => {
+                let rolls: Vec<String> = roll.rolls.iter().map(|roll| roll.to_string()).collect();
+                write!(f, "{} rolt {}: {} = {}", name, roll.dice, rolls.join(" + "), roll.total)
+            },
+            LogKind::Panic { name, roll, stress, effect } => {
+                write!(f, "{} doet een paniekcheck: {} tegen {} stress, ", name, roll, stress)?;
+                match effect {
+                    Some(effect) => write!(f, "paniek! {}", effect),
+                    None => write!(f, "blijft kalm"),
+                }
+            },
+            LogKind::Sweep { pos, readings } => {
+                write!(f, "Scan vanaf [{:.1}, {:.1}]:", pos.0, pos.1)?;
+                if readings.is_empty() {
+                    return write!(f, " geen signalen");
+                }
+                for (i, reading) in readings.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { "," };
+                    write!(f, "{} {} bij [{:.1}, {:.1}]", separator, reading.signal, reading.pos.0, reading.pos.1)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogCategory {
+    Warp,
+    Visit,
+    Event,
+    Scan,
+    Dice,
+}
+
+impl LogCategory {
+    pub const ALL: [LogCategory; 5] = [LogCategory::Warp, LogCategory::Visit, LogCategory::Event, LogCategory::Scan, LogCategory::Dice];
+}
+
+impl fmt::Display for LogCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            LogCategory::Warp => "Warps",
+            LogCategory::Visit => "Verkenningen",
+            LogCategory::Event => "Events",
+            LogCategory::Scan => "Scans",
+            LogCategory::Dice => "Worpen",
+        };
+        write!(f, "{}", res)
+    }
+}
@@ -1,25 +1,34 @@
 use std::io;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use chrono::Utc;
 use ratatui::{
-    crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind}, prelude::*, widgets::{Block, Paragraph, Widget}
+    crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind}, prelude::*, widgets::{Block, Clear, Paragraph, Widget}
 };
 use tachyonfx::{fx, EffectManager};
 
-use crate::{tui, user::User, util};
+use crate::{storage::Storage, tui, user::User, util};
+
+const MAX_FAILED_LOGINS: u8 = 3;
+const LOCKOUT_SECONDS: i64 = 60;
+const DENIED_DURATION: Duration = Duration::from_millis(2000);
 
 pub struct LoginScreen {
     exit: bool,
     pub username: String,
     pub password: String,
     password_selected: bool,
-    user_list: Vec<User>,
+    storage: Storage,
     user: Option<User>,
     effects: EffectManager<()>,
+    denied_at: Option<Instant>,
+    show_denied_effect: bool,
+    /// Username of the last failed login, the fields are cleared after a failure
+    last_failed: Option<String>,
 }
 
 impl LoginScreen {
-    pub fn new(user_list: Vec<User>) -> LoginScreen {
+    pub fn new(storage: Storage) -> LoginScreen {
         let mut effects: EffectManager<()> = EffectManager::default();
         effects.add_effect(
             fx::prolong_start(0, fx::coalesce(3000))
@@ -29,15 +38,24 @@ impl LoginScreen {
             username: String::new(),
             password: String::new(),
             password_selected: false,
-            user_list,
+            storage,
             user: None,
             effects,
+            denied_at: None,
+            show_denied_effect: false,
+            last_failed: None,
         }
     }
+
+    /// Hand back the storage, including any changes made while logging in
+    pub fn into_storage(self) -> Storage {
+        self.storage
+    }
 }
 
 impl LoginScreen {
-    pub fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<User> {
+    /// Returns the logged in user, or nothing when the program should shut down
+    pub fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<Option<User>> {
         let mut last_frame = Instant::now();
 
         while !self.exit {
@@ -47,11 +65,18 @@ impl LoginScreen {
             terminal.draw(|frame| {
                 let area = frame.area();
                 frame.render_widget(&mut *self, area);
+                if self.show_denied_effect {
+                    self.show_denied_effect = false;
+                    self.effects.add_effect(
+                        fx::sequence(&[fx::coalesce(400), fx::sleep(1200), fx::dissolve(400)])
+                            .with_area(denied_area(area))
+                    );
+                }
                 self.effects.process_effects(elapsed.into(), frame.buffer_mut(), area);
             })?;
             self.handle_events()?;
         }
-        Ok(self.user.clone().expect("user to be set"))
+        Ok(self.user.clone())
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
@@ -80,40 +105,76 @@ impl LoginScreen {
             },
             KeyCode::Tab => self.password_selected = !self.password_selected,
             KeyCode::Enter => {
+                let users = self.storage.users.clone();
                 self.user = self.try_login(self.username.clone(), self.password.clone());
-                if self.user == None {
-                    // TODO: show an error popup given login has failed
+                if self.user.is_none() {
+                    self.denied_at = Some(Instant::now());
+                    self.show_denied_effect = true;
+                    self.last_failed = Some(self.username.clone());
                     self.clear();
                 } else {
                     // Successfull login
                     self.exit = true;
                 }
+                // Keep track of discovered passwords and failed attempts
+                if self.storage.users != users {
+                    let _ = self.storage.clone().save();
+                }
             },
             _ => {},
         }
     }
 
     fn try_login(&mut self, username: String, password: String) -> Option<User> {
-        for user in self.user_list.iter_mut() {
+        let now = Utc::now().timestamp();
+        for user in self.storage.users.iter_mut() {
 
-            if user.password == "" {
+            if user.password.is_empty() {
                 let parts: Vec<&str> = password.split("-").collect();
                 if parts[0] == user.password_start {
                     if user.password_attempts >= user.password_attempts_max {
-                        user.password = password.clone();
+                        user.set_password(&password);
                     } else {
                         user.password_attempts += 1;
                     }
                 }
             }
 
-            if user.password != "" && user.username == username && user.password == password {
+            if user.username != username || user.password.is_empty() {
+                continue;
+            }
+            // Do not even check the password while locked out
+            if user.locked_until.is_some_and(|until| until > now) {
+                return None;
+            }
+            if user.verify_password(&password) {
+                user.failed_logins = 0;
+                user.locked_until = None;
                 return Some(user.clone());
             }
+            user.failed_logins += 1;
+            if user.failed_logins >= MAX_FAILED_LOGINS {
+                user.failed_logins = 0;
+                user.locked_until = Some(now + LOCKOUT_SECONDS);
+            }
         }
         None
     }
 
+    /// Seconds left before the typed username, or the last one that failed, can log in again
+    fn lockout_remaining(&self) -> Option<i64> {
+        let now = Utc::now().timestamp();
+        let username = match (self.username.is_empty(), &self.last_failed) {
+            (true, Some(last_failed)) => last_failed,
+            _ => &self.username,
+        };
+        self.storage.users.iter()
+            .find(|user| user.username == *username)
+            .and_then(|user| user.locked_until)
+            .map(|until| until - now)
+            .filter(|remaining| *remaining > 0)
+    }
+
     fn clear(&mut self) {
         self.username.clear();
         self.password.clear();
@@ -136,6 +197,12 @@ impl Widget for &mut LoginScreen {
             Line::from("*".repeat(self.password.len()).white()),
         ];
         text.extend(Text::from(lines));
+        if let Some(remaining) = self.lockout_remaining() {
+            text.extend(Text::from(vec![
+                Line::from(""),
+                Line::from(format!("GEBLOKKEERD: nog {}s", remaining).red().bold()),
+            ]));
+        }
 
         let area = util::center(
             area,
@@ -150,6 +217,22 @@ impl Widget for &mut LoginScreen {
             .block(block)
             .centered()
             .render(area, buf);
+
+        if self.denied_at.is_some_and(|denied| denied.elapsed() < DENIED_DURATION) {
+            let popup = denied_area(buf.area);
+            Clear.render(popup, buf);
+            Paragraph::new(vec![
+                Line::from(""),
+                Line::from("ACCESS DENIED".red().bold()),
+            ])
+                .block(Block::bordered().border_type(ratatui::widgets::BorderType::Double).red())
+                .centered()
+                .render(popup, buf);
+        }
     }
 }
 
+fn denied_area(area: Rect) -> Rect {
+    util::center(area, Constraint::Length(30), Constraint::Length(5))
+}
+
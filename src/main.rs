@@ -1,4 +1,4 @@
-use std::{panic};
+use std::{panic, time::{SystemTime, UNIX_EPOCH}};
 
 use color_eyre::{
     Result,
@@ -6,7 +6,7 @@ use color_eyre::{
     eyre,
 };
 
-use crate::storage::Storage;
+use crate::{error_screen::{ErrorChoice, ErrorScreen}, generator::GalaxyConfig, storage::{Storage, StorageError}};
 
 mod tui;
 mod app;
@@ -16,30 +16,98 @@ mod login;
 mod user;
 mod objects;
 mod components;
+mod generator;
+mod migration;
+mod error_screen;
+mod events;
+mod logbook;
+mod route;
+mod crew;
+mod dice;
 
 fn main() -> Result<()> {
-    let storage_path = std::env::args().nth(1);
+    let mut storage_path = None;
+    let mut seed = None;
+    let mut restore = None;
+    let mut list_backups = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = Some(parse_arg(args.next(), "--seed")?),
+            "--restore" => restore = Some(parse_arg(args.next(), "--restore")?),
+            "--backups" => list_backups = true,
+            _ => storage_path = Some(arg),
+        }
+    }
     install_hooks()?;
 
-    let storage = match storage_path {
-        Some(path) => Storage::load(path).expect("storage path to be valid"),
-        None => match Storage::load(String::from("default.json")) {
-            Ok(storage) => storage,
-            Err(err) => {
-                println!("{:?}", err);
-                Storage::new(String::from("fallback.json"))
-            }
-        },
+    if list_backups {
+        let path = storage_path.unwrap_or(String::from("default.json"));
+        for (i, backup) in Storage::list_backups(&path)?.iter().enumerate() {
+            println!("{}: {}", i, backup.display());
+        }
+        return Ok(());
+    }
+
+    let storage_path = storage_path.unwrap_or(String::from("default.json"));
+    let mut result = match restore {
+        Some(index) => Ok(Storage::restore(storage_path, index)?),
+        None => Storage::load(storage_path),
     };
     let mut terminal = tui::init()?;
 
-    let user = login::LoginScreen::new(storage.users.clone()).run(&mut terminal)?;
-    let _app_result = app::App::new(storage, user).run(&mut terminal);
+    let mut storage = loop {
+        match result {
+            Ok(storage) => break storage,
+            Err(err) => {
+                // Never generate over a save that exists but could not be read
+                let path = match &err {
+                    StorageError::NotFound(path) => path.clone(),
+                    _ => String::from("fallback.json"),
+                };
+                match ErrorScreen::new(err).run(&mut terminal)? {
+                    ErrorChoice::Generate => break generate_storage(path, seed),
+                    ErrorChoice::Open(path) => result = Storage::load(path),
+                    ErrorChoice::Quit => {
+                        tui::restore()?;
+                        return Ok(());
+                    },
+                }
+            },
+        }
+    };
+
+    // Groups take turns, keep switching between logging in and the app until shutdown
+    loop {
+        let mut login = login::LoginScreen::new(storage);
+        let user = login.run(&mut terminal)?;
+        storage = login.into_storage();
+        let Some(user) = user else { break };
+
+        let mut app = app::App::new(storage, user);
+        let exit = app.run(&mut terminal)?;
+        storage = app.into_storage();
+        if let app::AppExit::Quit = exit { break }
+    }
 
     tui::restore()?;
     Ok(())
 }
 
+/// Number after a flag, a typo should not start a new galaxy with the wrong seed
+fn parse_arg<T: std::str::FromStr>(value: Option<String>, flag: &str) -> Result<T> {
+    let value = value.ok_or_else(|| eyre::eyre!("{} needs a number", flag))?;
+    value.parse().map_err(|_| eyre::eyre!("{} needs a number, got \"{}\"", flag, value))
+}
+
+/// Generate a new galaxy, the seed is stored so the session can be reproduced
+fn generate_storage(path: String, seed: Option<u64>) -> Storage {
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+    });
+    Storage::generate(path, &GalaxyConfig::new(seed))
+}
+
 pub fn install_hooks() -> color_eyre::Result<()> {
     let (panic_hook, eyre_hook) = HookBuilder::default().into_hooks();
 
@@ -0,0 +1,150 @@
+use color_eyre::{eyre::eyre, Result};
+use serde_json::{json, Value};
+
+use crate::user::{hash_password, is_hashed};
+
+/// Version of the save format written by this build
+pub const CURRENT_VERSION: u32 = 2;
+
+type Migration = fn(&mut Value) -> Result<()>;
+
+/// Migration at index `i` upgrades a save from version `i` to `i + 1`
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [
+    v0_planet_environment,
+    hash_passwords,
+];
+
+/// Upgrade a raw save file step by step to the current version.
+/// Users are added to the save by hand, so plain text passwords are hashed on every load.
+pub fn migrate(value: &mut Value) -> Result<()> {
+    let version = match value.get("version") {
+        Some(version) => version.as_u64().ok_or_else(|| eyre!("version is not a number"))? as u32,
+        None => 0,
+    };
+    if version > CURRENT_VERSION {
+        return Err(eyre!("save version {} is newer than the supported version {}", version, CURRENT_VERSION));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(value).map_err(|err| eyre!("migrating from version {}: {}", from, err))?;
+        value["version"] = json!(from as u32 + 1);
+    }
+    hash_passwords(value)
+}
+
+fn planets_mut(value: &mut Value) -> impl Iterator<Item = &mut Value> {
+    value.get_mut("map")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(|system| system.get_mut("planets").and_then(Value::as_array_mut))
+        .flatten()
+}
+
+/// Planets used to have a hand picked radius, this became the size of the planet environment
+fn v0_planet_environment(value: &mut Value) -> Result<()> {
+    for planet in planets_mut(value) {
+        let Some(planet) = planet.as_object_mut() else { continue };
+        let Some(radius) = planet.remove("radius").and_then(|radius| radius.as_f64()) else { continue };
+        if planet.contains_key("environment") {
+            continue;
+        }
+        let size = match radius {
+            r if r <= 2.5 => "Small",
+            r if r <= 3.5 => "Medium",
+            r if r <= 5.0 => "Large",
+            _ => "Huge",
+        };
+        // Earth like values for everything that was never rolled
+        planet.insert(String::from("environment"), json!({
+            "size": size,
+            "temp": 15,
+            "gravity": 1.0,
+            "hours_per_day": 24,
+        }));
+    }
+    Ok(())
+}
+
+/// Passwords used to be stored as plain text, and still are when typed into the save by hand
+fn hash_passwords(value: &mut Value) -> Result<()> {
+    let users = value.get_mut("users").and_then(Value::as_array_mut).into_iter().flatten();
+    for user in users {
+        let Some(password) = user.get("password").and_then(Value::as_str) else { continue };
+        if !password.is_empty() && !is_hashed(password) {
+            user["password"] = json!(hash_password(password));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+
+    /// Save file from before planet environments and hashed passwords
+    fn v0_save() -> Value {
+        json!({
+            "users": [
+                { "username": "schip1", "password": "geheim" },
+                { "username": "schip2", "password": "" },
+            ],
+            "map": [{
+                "name": "Korvax-7",
+                "pos": [3.0, 4.0],
+                "planets": [
+                    { "name": "Korvax-7 I", "radius": 2.0 },
+                    { "name": "Korvax-7 II", "radius": 6.0 },
+                ],
+            }],
+        })
+    }
+
+    #[test]
+    fn v0_upgrades_to_current_version() {
+        let mut value = v0_save();
+        migrate(&mut value).expect("v0 save to migrate");
+        assert_eq!(value["version"], json!(CURRENT_VERSION));
+
+        let planets = &value["map"][0]["planets"];
+        assert_eq!(planets[0]["environment"]["size"], json!("Small"));
+        assert_eq!(planets[1]["environment"]["size"], json!("Huge"));
+        assert!(planets[0].get("radius").is_none());
+
+        let storage: Storage = serde_json::from_value(value).expect("migrated save to load");
+        assert!(storage.users[0].verify_password("geheim"));
+        assert!(storage.users[1].password.is_empty());
+    }
+
+    #[test]
+    fn hand_added_passwords_are_hashed() {
+        let mut value = json!({
+            "version": CURRENT_VERSION,
+            "users": [
+                { "username": "schip1", "password": "geheim" },
+                { "username": "schip2", "password": "" },
+            ],
+        });
+        migrate(&mut value).expect("current save to migrate");
+        assert_eq!(value["version"], json!(CURRENT_VERSION));
+
+        let storage: Storage = serde_json::from_value(value).expect("migrated save to load");
+        assert!(storage.users[0].verify_password("geheim"));
+        assert!(storage.users[1].password.is_empty());
+    }
+
+    #[test]
+    fn hashed_passwords_are_left_alone() {
+        let hash = hash_password("geheim");
+        let mut value = json!({ "version": CURRENT_VERSION, "users": [{ "username": "schip1", "password": hash }] });
+        migrate(&mut value).expect("current save to migrate");
+        assert_eq!(value["users"][0]["password"], json!(hash));
+    }
+
+    #[test]
+    fn newer_version_is_refused() {
+        let mut value = json!({ "version": CURRENT_VERSION + 1 });
+        assert!(migrate(&mut value).is_err());
+    }
+}
@@ -3,9 +3,12 @@ use std::{collections::HashSet, fmt};
 use ratatui::{prelude::*, style::Color, widgets::canvas::{Circle, Context}};
 use serde::{Deserialize, Serialize};
 
-use crate::{components::star_map::StarMap, util::{Event, ItemDiff}};
+use rand::Rng;
 
-#[derive(Deserialize, Serialize, Clone)]
+use crate::{components::star_map::StarMap, logbook::LogKind, util::{Event, ItemDiff}};
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
 pub struct SolarSystem {
     pub name: String,
     pub pos: (f64, f64),
@@ -30,31 +33,98 @@ impl SolarSystem {
         }
         false
     }
+
+    /// What a sensor sweep picks up, the readings are not always right
+    pub fn signal(&self, rng: &mut impl Rng) -> Option<Signal> {
+        let signal = match (self.has_component(), self.has_event()) {
+            (true, true) => Some(Signal::Strong),
+            (true, false) => Some(Signal::Energy),
+            (false, true) => Some(Signal::Anomaly),
+            (false, false) => None,
+        };
+        match signal {
+            Some(_) if rng.gen_bool(0.25) => Some(Signal::Faint),
+            None if rng.gen_bool(0.1) => Some(Signal::Faint),
+            signal => signal,
+        }
+    }
+}
+
+/// Fuzzy reading of a sensor sweep
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum Signal {
+    Strong,
+    Energy,
+    Anomaly,
+    Faint,
+}
+
+impl Signal {
+    pub fn color(&self) -> Color {
+        match self {
+            Signal::Strong => Color::Red,
+            Signal::Energy => Color::Magenta,
+            Signal::Anomaly => Color::Yellow,
+            Signal::Faint => Color::DarkGray,
+        }
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            Signal::Strong => "sterke energiesignatuur",
+            Signal::Energy => "energiesignatuur",
+            Signal::Anomaly => "anomalie gedetecteerd",
+            Signal::Faint => "zwak signaal",
+        };
+        write!(f, "{}", res)
+    }
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+/// A signal picked up at a position on the galactic map
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SignalReading {
+    pub pos: (f64, f64),
+    pub signal: Signal,
+}
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
 pub struct Planet {
     // Visual traits
-    name: String,
+    pub name: String,
     x: f64,
     y: f64,
-    radius: f64,
-
-    // TODO: Random info, show in popup
-    // temp: i32,
-    // gravity: f32,
-    // hours_per_day: u32,
     planet_type: PlanetType,
 
+    // Random info, shown in the planet details popup
+    environment: Environment,
+
     // Game related
-    has_event: bool,
-    has_component: bool,
+    pub has_event: bool,
+    pub has_component: bool,
     crystals: i32,
     fuel: i32,
-    visited_by: HashSet<String>,
+    pub visited_by: HashSet<String>,
 }
 
 impl Planet {
+    pub fn new(name: String, pos: (f64, f64), planet_type: PlanetType, environment: Environment, crystals: i32, fuel: i32) -> Planet {
+        Planet {
+            name,
+            x: pos.0,
+            y: pos.1,
+            planet_type,
+            environment,
+            has_event: false,
+            has_component: false,
+            crystals,
+            fuel,
+            visited_by: HashSet::new(),
+        }
+    }
+
     pub fn visit(&mut self, name: String) -> Vec<Event> {
         self.visited_by.insert(name);
         let mut events = Vec::new();
@@ -67,19 +137,68 @@ impl Planet {
         self.fuel = 0;
         if self.has_event {
             self.has_event = false;
-            events.push(Event::RandomEvent);
+            events.push(Event::RandomEvent(self.name.clone()));
         }
         if self.has_component {
             self.has_component = false;
             diff.components += 1;
         }
+        events.push(Event::Log(LogKind::Visit {
+            planet: self.name.clone(),
+            crystals: diff.crystals,
+            fuel: diff.fuel,
+            components: diff.components,
+        }));
         events.push(Event::Item(diff));
         events.push(Event::PlanetUpdate);
         events
     }
 
+    pub fn details(&self) -> Vec<Line<'static>> {
+        let env = &self.environment;
+        let day = match env.hours_per_day {
+            0 => String::from("Gebonden rotatie"),
+            hours => format!("{} uur", hours),
+        };
+        let mut visitors: Vec<String> = self.visited_by.iter().cloned().collect();
+        visitors.sort();
+        let explored = match self.visited_by.is_empty() {
+            true => "Nee".red(),
+            false => "Ja".green(),
+        };
+
+        let mut lines = vec![
+            Line::from(self.name.clone().bold()),
+            Line::from(""),
+            Line::from(format!("Type: {}", self.planet_type)),
+            Line::from(format!("Grootte: {}", env.size)),
+            Line::from(format!("Atmosfeer: {}", env.atmosphere)),
+            Line::from(format!("Temperatuur: {} C", env.temp)),
+            Line::from(format!("Zwaartekracht: {:.2} g", env.gravity)),
+            Line::from(format!("Daglengte: {}", day)),
+            Line::from(format!("Biosfeer: {}", env.biosphere)),
+            Line::from(""),
+            Line::from(vec!["Verkend: ".into(), explored]),
+        ];
+        if !visitors.is_empty() {
+            lines.push(Line::from(format!("Bezocht door: {}", visitors.join(", "))));
+        }
+        if !env.points_of_interest.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from("Bezienswaardigheden:"));
+            for poi in &env.points_of_interest {
+                lines.push(Line::from(format!("- {}", poi)));
+            }
+        }
+        lines
+    }
+
+    fn radius(&self) -> f64 {
+        self.environment.size.radius()
+    }
+
     pub fn draw(&self, ctx: &mut Context, highlighted: Option<Color>) {
-        let mut i = self.radius;
+        let mut i = self.radius();
         while i > 0.0 {
             ctx.draw(&Circle {
                 x: self.x,
@@ -92,43 +211,135 @@ impl Planet {
         ctx.draw(&Circle {
             x: self.x,
             y: self.y,
-            radius: self.radius * 1.7,
+            radius: self.radius() * 1.7,
             color: highlighted.unwrap_or(Color::DarkGray),
         });
     }
 
     pub fn draw_current(&self, ctx: &mut Context) {
         ctx.print(
-            self.x-(self.radius/2.0), self.y+(self.radius*2.0),
+            self.x-(self.radius()/2.0), self.y+(self.radius()*2.0),
             "Jij bent hier".green().bold()
             // "You are here".green().bold()
         );
     }
 
     fn get_color(&self) -> Color {
+        let temp = self.environment.temp;
         match self.planet_type {
+            PlanetType::Gas if temp > 100 => Color::Yellow,
             PlanetType::Gas => Color::Green,
+            PlanetType::Ocean if temp < -20 => Color::White,
             PlanetType::Ocean => Color::Blue,
+            PlanetType::Terrestrial if temp < -50 => Color::Gray,
+            PlanetType::Terrestrial if self.environment.biosphere != Biosphere::None => Color::LightGreen,
             PlanetType::Terrestrial => Color::Red,
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Clone)]
-enum PlanetSize {
+/// Physical traits of a planet, rolled by the planet generator
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct Environment {
+    pub size: PlanetSize,
+    pub atmosphere: Atmosphere,
+    /// Average surface temperature in degrees Celsius
+    pub temp: i32,
+    /// Surface gravity in g
+    pub gravity: f32,
+    /// Length of a day in hours, 0 when the planet is tidally locked
+    pub hours_per_day: u32,
+    pub biosphere: Biosphere,
+    pub points_of_interest: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub enum PlanetSize {
     Small,
+    #[default]
     Medium,
     Large,
     Huge,
 }
 
-#[derive(Deserialize, Serialize, Clone)]
-enum PlanetType {
+impl PlanetSize {
+    pub fn radius(&self) -> f64 {
+        match self {
+            PlanetSize::Small => 2.0,
+            PlanetSize::Medium => 3.0,
+            PlanetSize::Large => 4.5,
+            PlanetSize::Huge => 6.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+pub enum PlanetType {
     Gas,
+    #[default]
     Terrestrial,
     Ocean,
 }
 
+impl fmt::Display for PlanetSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            PlanetSize::Small => "Klein",
+            PlanetSize::Medium => "Middelgroot",
+            PlanetSize::Large => "Groot",
+            PlanetSize::Huge => "Enorm",
+        };
+        write!(f, "{}", res)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+pub enum Atmosphere {
+    #[default]
+    None,
+    Thin,
+    Breathable,
+    Dense,
+    Toxic,
+    Corrosive,
+}
+
+impl fmt::Display for Atmosphere {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            Atmosphere::None => "Geen",
+            Atmosphere::Thin => "Dun",
+            Atmosphere::Breathable => "Ademhaalbaar",
+            Atmosphere::Dense => "Dicht",
+            Atmosphere::Toxic => "Giftig",
+            Atmosphere::Corrosive => "Bijtend",
+        };
+        write!(f, "{}", res)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+pub enum Biosphere {
+    #[default]
+    None,
+    Microbial,
+    Flora,
+    Fauna,
+}
+
+impl fmt::Display for Biosphere {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let res = match self {
+            Biosphere::None => "Geen",
+            Biosphere::Microbial => "Microbieel",
+            Biosphere::Flora => "Flora",
+            Biosphere::Fauna => "Flora en fauna",
+        };
+        write!(f, "{}", res)
+    }
+}
+
 impl fmt::Display for PlanetType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let res = match self {
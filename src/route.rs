@@ -0,0 +1,116 @@
+use crate::{objects::SolarSystem, user::Ship, util::distance};
+
+/// Chain of warps to a solar system, each step is an index into the map
+#[derive(Clone, Debug, PartialEq)]
+pub struct Route {
+    pub jumps: Vec<usize>,
+    pub fuel: i32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RouteError {
+    /// No chain of warps within range reaches the system
+    Unreachable,
+    /// The route needs more fuel than there is on board
+    NotEnoughFuel(i32),
+}
+
+/// Cheapest chain of warps from `start` to the `target` system, jumping only between systems
+/// within warp range that are `visible`. Ties in fuel are broken by the amount of jumps.
+pub fn plan_route(systems: &[SolarSystem], visible: &[bool], start: (f64, f64), target: usize, ship: &Ship, fuel: i32) -> Result<Route, RouteError> {
+    // Node 0 is the start position, node i + 1 is system i
+    let nodes = systems.len() + 1;
+    let pos = |node: usize| if node == 0 { start } else { systems[node - 1].pos };
+
+    let mut cost: Vec<Option<(i32, usize)>> = vec![None; nodes];
+    let mut previous: Vec<Option<usize>> = vec![None; nodes];
+    let mut done = vec![false; nodes];
+    cost[0] = Some((0, 0));
+
+    // Few systems on a map, a linear search for the cheapest node is fine
+    while let Some(current) = (0..nodes)
+        .filter(|node| !done[*node] && cost[*node].is_some())
+        .min_by_key(|node| cost[*node])
+    {
+        done[current] = true;
+        if current == target + 1 {
+            break;
+        }
+
+        let (current_fuel, current_jumps) = cost[current].expect("visited node to have a cost");
+        for next in 1..nodes {
+            let distance = distance(pos(current), pos(next));
+            if done[next] || !visible[next - 1] || distance > ship.warp_range {
+                continue;
+            }
+            let candidate = (current_fuel + ship.fuel_cost(distance), current_jumps + 1);
+            if cost[next].is_none_or(|known| candidate < known) {
+                cost[next] = Some(candidate);
+                previous[next] = Some(current);
+            }
+        }
+    }
+
+    let (total, _) = cost[target + 1].ok_or(RouteError::Unreachable)?;
+    if total > fuel {
+        return Err(RouteError::NotEnoughFuel(total));
+    }
+
+    let mut jumps = Vec::new();
+    let mut node = target + 1;
+    while node != 0 {
+        jumps.push(node - 1);
+        node = previous[node].expect("reached node to have a previous node");
+    }
+    jumps.reverse();
+    Ok(Route { jumps, fuel: total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Systems 2 apart on a line, a default ship can only warp to its neighbours
+    fn line(count: usize) -> Vec<SolarSystem> {
+        (0..count).map(|i| SolarSystem {
+            name: format!("Systeem {}", i),
+            pos: ((i + 1) as f64 * 2.0, 0.0),
+            planets: Vec::new(),
+        }).collect()
+    }
+
+    #[test]
+    fn direct_jump() {
+        let systems = line(3);
+        let route = plan_route(&systems, &[true; 3], (0.0, 0.0), 0, &Ship::default(), 10);
+        assert_eq!(route, Ok(Route { jumps: vec![0], fuel: 2 }));
+    }
+
+    #[test]
+    fn hops_through_systems_out_of_warp_range() {
+        let systems = line(3);
+        let route = plan_route(&systems, &[true; 3], (0.0, 0.0), 2, &Ship::default(), 10);
+        assert_eq!(route, Ok(Route { jumps: vec![0, 1, 2], fuel: 6 }));
+    }
+
+    #[test]
+    fn hidden_systems_are_skipped() {
+        let systems = line(3);
+        let route = plan_route(&systems, &[true, false, true], (0.0, 0.0), 2, &Ship::default(), 10);
+        assert_eq!(route, Err(RouteError::Unreachable));
+    }
+
+    #[test]
+    fn too_far_for_the_warp_range() {
+        let systems = vec![SolarSystem { name: String::from("Ver weg"), pos: (10.0, 0.0), planets: Vec::new() }];
+        let route = plan_route(&systems, &[true], (0.0, 0.0), 0, &Ship::default(), 10);
+        assert_eq!(route, Err(RouteError::Unreachable));
+    }
+
+    #[test]
+    fn not_enough_fuel() {
+        let systems = line(3);
+        let route = plan_route(&systems, &[true; 3], (0.0, 0.0), 2, &Ship::default(), 5);
+        assert_eq!(route, Err(RouteError::NotEnoughFuel(6)));
+    }
+}
@@ -1,45 +1,218 @@
-use std::{fs::File, io::{Read, Write}};
+use std::{fmt, fs::{self, File}, io::{self, Read, Write}, path::{Path, PathBuf}, time::Duration};
 
+use chrono::Local;
 use serde::{Deserialize, Serialize};
-use color_eyre::Result;
+use color_eyre::{eyre::eyre, Result};
 
-use crate::{objects::SolarSystem, user::User};
+use crate::{events::{default_events, EventDefinition, EventStatus, QueuedEvent}, generator::{generate_galaxy, GalaxyConfig}, migration::{self, CURRENT_VERSION}, objects::SolarSystem, user::User};
+
+const BACKUP_EXTENSION: &str = "bak";
+/// Saves in quick succession share a backup, so the backups reach further back than the last few minutes
+const BACKUP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug)]
+pub enum StorageError {
+    NotFound(String),
+    Parse { path: String, line: usize, column: usize, message: String },
+    Schema { path: String, message: String },
+    Io { path: String, error: io::Error },
+}
+
+impl StorageError {
+    fn from_json(path: &str, err: serde_json::Error) -> StorageError {
+        match err.classify() {
+            serde_json::error::Category::Data => StorageError::Schema {
+                path: path.to_string(),
+                message: err.to_string(),
+            },
+            _ => StorageError::Parse {
+                path: path.to_string(),
+                line: err.line(),
+                column: err.column(),
+                message: err.to_string(),
+            },
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            StorageError::NotFound(path) => path,
+            StorageError::Parse { path, .. } => path,
+            StorageError::Schema { path, .. } => path,
+            StorageError::Io { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::NotFound(path) => write!(f, "Opslagbestand {} niet gevonden", path),
+            StorageError::Parse { path, line, column, message } => {
+                write!(f, "Opslagbestand {} is beschadigd op regel {}, kolom {}: {}", path, line, column, message)
+            },
+            StorageError::Schema { path, message } => {
+                write!(f, "Opslagbestand {} past niet bij deze versie: {}", path, message)
+            },
+            StorageError::Io { path, error } => write!(f, "Kan {} niet lezen: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
 
 #[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
 pub struct Storage {
+    /// Version of the save format, older saves are migrated on load
+    pub version: u32,
     pub path: String,
     pub users: Vec<User>,
     pub components: i32,
     pub map: Vec<SolarSystem>,
+    /// Seed the map was generated with, if it was generated
+    pub seed: Option<u64>,
+    /// Amount of backups kept next to the save file
+    pub backups: usize,
+    /// Random events that can happen when exploring a planet
+    pub events: Vec<EventDefinition>,
+    /// Triggered events for the game master, kept after resolving as a history
+    pub event_queue: Vec<QueuedEvent>,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Storage::new(String::new())
+    }
 }
 
 impl Storage {
     pub fn new(path: String) -> Storage {
         Storage {
-            path: path,
+            version: CURRENT_VERSION,
+            path,
             users: Vec::new(),
             map: Vec::new(),
             components: 0,
+            seed: None,
+            backups: 5,
+            events: default_events(),
+            event_queue: Vec::new(),
+        }
+    }
+
+    /// New galaxy with a game master in the first system, so there is always someone to log in with
+    pub fn generate(path: String, config: &GalaxyConfig) -> Storage {
+        let map = generate_galaxy(config);
+        let start = map.first().map(|system| system.pos).unwrap_or_default();
+        Storage {
+            users: vec![User::game_master(start)],
+            map,
+            seed: Some(config.seed),
+            ..Storage::new(path)
         }
     }
 
-    pub fn load(storage_path: String) -> Result<Storage> {
-        let mut file = File::open(storage_path)?;
+    pub fn load(storage_path: String) -> Result<Storage, StorageError> {
+        let io_error = |error: io::Error| match error.kind() {
+            io::ErrorKind::NotFound => StorageError::NotFound(storage_path.clone()),
+            _ => StorageError::Io { path: storage_path.clone(), error },
+        };
+        let mut file = File::open(&storage_path).map_err(io_error)?;
         let mut buffer = String::new();
-        file.read_to_string(&mut buffer)?;
+        file.read_to_string(&mut buffer).map_err(io_error)?;
 
-        let result: Storage = serde_json::from_str(&buffer).expect("JSON to be valid");
+        let mut value: serde_json::Value = serde_json::from_str(&buffer)
+            .map_err(|err| StorageError::from_json(&storage_path, err))?;
+        migration::migrate(&mut value).map_err(|err| StorageError::Schema {
+            path: storage_path.clone(),
+            message: err.to_string(),
+        })?;
+        let mut result: Storage = serde_json::from_value(value)
+            .map_err(|err| StorageError::from_json(&storage_path, err))?;
+        if result.path.is_empty() {
+            result.path = storage_path;
+        }
 
         Ok(result)
     }
 
-    pub fn save(self) -> Result<()> {
-        let mut file = File::create(&self.path)?;
+    /// Load a backup of the save file, 0 being the most recent one.
+    /// The storage keeps the original path, so the next save replaces the broken file.
+    pub fn restore(storage_path: String, index: usize) -> Result<Storage> {
+        let backups = Storage::list_backups(&storage_path)?;
+        let backup = backups.get(index)
+            .ok_or_else(|| eyre!("no backup {} for {}, found {} backups", index, storage_path, backups.len()))?;
+
+        let mut result = Storage::load(backup.to_string_lossy().to_string())?;
+        result.path = storage_path;
+        Ok(result)
+    }
 
+    /// All backups of the given save file, most recent first
+    pub fn list_backups(storage_path: &str) -> Result<Vec<PathBuf>> {
+        let path = Path::new(storage_path);
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let prefix = format!("{}.", path.file_name().unwrap_or_default().to_string_lossy());
+        let suffix = format!(".{}", BACKUP_EXTENSION);
+
+        let mut backups: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|backup| {
+                let name = backup.file_name().unwrap_or_default().to_string_lossy();
+                name.starts_with(&prefix) && name.ends_with(&suffix)
+            })
+            .collect();
+        // The timestamp in the name sorts chronologically
+        backups.sort();
+        backups.reverse();
+        Ok(backups)
+    }
+
+    /// Write to a temporary file first and move it over the save file once it is on disk,
+    /// so a crash halfway through never leaves a half written save behind.
+    pub fn save(self) -> Result<()> {
+        let tmp_path = format!("{}.tmp", self.path);
+        let mut file = File::create(&tmp_path)?;
         file.write_all(&serde_json::to_vec_pretty(&self)?)?;
+        file.sync_all()?;
+
+        // Backups are nice to have, they should never stop the save itself
+        let backed_up = self.backups > 0 && Path::new(&self.path).exists() && self.backup_due() && {
+            let backup = format!("{}.{}.{}", self.path, Local::now().format("%Y%m%d-%H%M%S%3f"), BACKUP_EXTENSION);
+            fs::copy(&self.path, backup).is_ok()
+        };
+
+        fs::rename(&tmp_path, &self.path)?;
+
+        if backed_up {
+            for old in Storage::list_backups(&self.path).unwrap_or_default().iter().skip(self.backups) {
+                let _ = fs::remove_file(old);
+            }
+        }
         Ok(())
     }
 
+    /// Whether the newest backup is old enough to make a new one
+    fn backup_due(&self) -> bool {
+        let newest = Storage::list_backups(&self.path).ok()
+            .and_then(|backups| backups.first().cloned())
+            .and_then(|backup| fs::metadata(backup).and_then(|metadata| metadata.modified()).ok());
+        match newest {
+            Some(modified) => modified.elapsed().map_or(true, |age| age >= BACKUP_INTERVAL),
+            None => true,
+        }
+    }
+
+    /// Whether the user still has events the game master has not resolved
+    pub fn has_pending_events(&self, username: &str) -> bool {
+        self.event_queue.iter().any(|event| event.user == username && event.status != EventStatus::Resolved)
+    }
+
     pub fn update_user(&mut self, user: &User) {
         for u in self.users.iter_mut() {
             if u.username == user.username {
@@ -48,4 +221,3 @@ impl Storage {
         }
     }
 }
-
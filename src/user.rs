@@ -1,17 +1,147 @@
+use std::collections::HashSet;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+use crate::{crew::{default_crew, CrewMember}, events::Marker, logbook::LogEntry};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum Role {
+    #[default]
+    Player,
+    /// De leiding, can view and edit all ships
+    GameMaster,
+}
+
+/// Hash a password with a random salt, the result is a PHC string that includes the salt
+pub fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("argon2 to hash the password")
+        .to_string()
+}
+
+pub fn is_hashed(password: &str) -> bool {
+    password.starts_with("$argon2")
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
 pub struct User {
     pub username: String,
+    pub role: Role,
     pub password_start: String,
     pub password_attempts: u8,
     pub password_attempts_max: u8,
+    /// Argon2 hash of the password, empty until the password has been discovered
     pub password: String,
     pub pos_x: f64,
     pub pos_y: f64,
 
+    // Login
+    pub failed_logins: u8,
+    /// Unix timestamp until which logging in is blocked
+    pub locked_until: Option<i64>,
+
     // Resources,
     pub fuel: i32,
     pub crystals: i32,
     pub reputation: i32,
+    pub ship: Ship,
+
+    /// Places of interest found during random events
+    pub markers: Vec<Marker>,
+    pub logbook: Vec<LogEntry>,
+    /// Names of the solar systems this user has visited
+    pub discovered: HashSet<String>,
+    /// Users without a crew in the save file start with the default crew
+    #[serde(default = "default_crew")]
+    pub crew: Vec<CrewMember>,
+}
+
+/// Name and password of the game master in a freshly generated galaxy
+pub const DEFAULT_GAME_MASTER: &str = "leiding";
+/// Enough fuel for the first few warps
+const STARTING_FUEL: i32 = 10;
+
+impl User {
+    /// Game master to log in with after generating a galaxy
+    pub fn game_master(pos: (f64, f64)) -> User {
+        let mut user = User {
+            username: String::from(DEFAULT_GAME_MASTER),
+            role: Role::GameMaster,
+            pos_x: pos.0,
+            pos_y: pos.1,
+            fuel: STARTING_FUEL,
+            crew: default_crew(),
+            ..Default::default()
+        };
+        user.set_password(DEFAULT_GAME_MASTER);
+        user
+    }
+
+    pub fn set_password(&mut self, password: &str) {
+        self.password = hash_password(password);
+    }
+
+    pub fn verify_password(&self, password: &str) -> bool {
+        match PasswordHash::new(&self.password) {
+            Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Upgradable stats of the ship of a user
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Ship {
+    pub engine_level: u8,
+    /// Maximum distance of a single warp
+    pub warp_range: f64,
+    /// Distance travelled per unit of fuel
+    pub fuel_efficiency: f64,
+    /// Systems this close to a visited system show up on the map
+    pub sensor_range: f64,
+}
+
+impl Default for Ship {
+    fn default() -> Self {
+        Ship {
+            engine_level: 1,
+            warp_range: 3.0,
+            fuel_efficiency: 1.5,
+            sensor_range: 4.0,
+        }
+    }
+}
+
+impl Ship {
+    /// Every warp costs at least 1 fuel, longer warps cost more
+    pub fn fuel_cost(&self, distance: f64) -> i32 {
+        ((distance / self.fuel_efficiency).ceil() as i32).max(1)
+    }
+
+    /// Crystals spent on a sensor sweep
+    pub const SWEEP_COST: i32 = 5;
+
+    /// A sweep reaches further than the sensors do on their own
+    pub fn sweep_radius(&self) -> f64 {
+        self.sensor_range * 2.0
+    }
+
+    pub fn upgrade_cost(&self) -> i32 {
+        20 * self.engine_level as i32
+    }
+
+    pub fn upgrade(&mut self) {
+        self.engine_level += 1;
+        self.warp_range += 1.0;
+        self.fuel_efficiency += 0.25;
+        self.sensor_range += 0.5;
+    }
 }
@@ -1,9 +1,11 @@
+use chrono::{DateTime, Local};
 use num_traits::abs;
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
 
-use crate::objects::SolarSystem;
+use crate::{logbook::LogKind, objects::SolarSystem};
 
 pub const WARP_HOLD_DURATION: u64 = 1;
+pub const MAP_SIZE: f64 = 30.0;
 pub const TITLE_HEADER: &str = r#"
      _                      _______                      _      
   _dMMMb._              .adOOOOOOOOOba.              _,dMMMb_   
@@ -27,11 +29,27 @@ pub const TITLE_HEADER: &str = r#"
 M0TH3R@3-OS
 "#;
 
+#[allow(clippy::enum_variant_names)]
 pub enum Event {
     Item(ItemDiff),
     NewSystem(Option<SolarSystem>),
     PlanetUpdate,
-    RandomEvent,
+    /// Triggered on the planet with the given name
+    RandomEvent(String),
+    /// A user was edited by the game master
+    UserUpdate(String),
+    /// Spend crystals on a better warp drive
+    UpgradeShip,
+    /// Spend crystals to scan the systems around the ship
+    SensorSweep,
+    /// A crew member was edited
+    CrewUpdate,
+    /// The away team went down to the planet
+    Land { planet: String, party: Vec<String> },
+    /// Bring every crew member back to the ship
+    Recall,
+    /// Something worth writing down in the logbook
+    Log(LogKind),
 }
 
 pub struct ItemDiff {
@@ -40,6 +58,13 @@ pub struct ItemDiff {
     pub components: i32,
 }
 
+pub fn format_timestamp(timestamp: i64) -> String {
+    match DateTime::from_timestamp(timestamp, 0) {
+        Some(time) => time.with_timezone(&Local).format("%d-%m %H:%M").to_string(),
+        None => String::from("??-?? ??:??"),
+    }
+}
+
 pub fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
     let [area] = Layout::horizontal([horizontal])
         .flex(Flex::Center)